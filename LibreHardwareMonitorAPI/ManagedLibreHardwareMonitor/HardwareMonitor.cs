@@ -42,12 +42,38 @@ namespace ManagedLibreHardwareMonitor
             return jsonString;
         }
 
+        // reset the minimum and maximum values of every sensor on the hardware with the identifier
+        public void ResetMinMax(string identifier)
+        {
+            foreach (IHardware hardware in _computer.Hardware)
+                ResetMinMax(hardware, identifier);
+        }
+
+        // reset the sensors of the hardware if it matches, sub-hardware is reset with its parent
+        private void ResetMinMax(IHardware hardware, string identifier)
+        {
+            bool matches = hardware.Identifier.ToString() == identifier;
+
+            if (matches)
+            {
+                foreach (ISensor sensor in hardware.Sensors)
+                {
+                    sensor.ResetMin();
+                    sensor.ResetMax();
+                }
+            }
+
+            foreach (IHardware subHardware in hardware.SubHardware)
+                ResetMinMax(subHardware, matches ? subHardware.Identifier.ToString() : identifier);
+        }
+
         // parse the hardware data into a custom data structure
         private Hardware[] ParseHardware(IEnumerable<IHardware> hardwareList)
         {
             return hardwareList.Select(h => new Hardware
             {
                 HardwareType = h.HardwareType,
+                Identifier = h.Identifier.ToString(),
                 Name = h.Name,
                 SubHardware = ParseHardware(h.SubHardware),
                 Sensors = parseSensors(h.Sensors)
@@ -60,6 +86,7 @@ namespace ManagedLibreHardwareMonitor
             return sensorList.Select(s => new Sensor
             {
                 SensorType = s.SensorType,
+                Identifier = s.Identifier.ToString(),
                 Name = s.Name,
                 Index = s.Index,
                 Value = s.Value.GetValueOrDefault(),
@@ -90,6 +117,8 @@ namespace ManagedLibreHardwareMonitor
     {
         public HardwareType HardwareType { get; set; }
 
+        public string Identifier { get; set; }
+
         public string Name { get; set; }
 
         public Hardware[] SubHardware { get; set; }
@@ -102,6 +131,8 @@ namespace ManagedLibreHardwareMonitor
     {
         public SensorType SensorType { get; set; }
 
+        public string Identifier { get; set; }
+
         public string Name { get; set; }
 
         public int Index { get; set; }
@@ -23,14 +23,24 @@ void UpdateHardwareMonitor(void* handle)
     (*wrapperHandle)->Update();
 }
 
-// function to get a report from the hardware monitor instance
-void GetReport(void* handle, char* buffer, int bufferSize)
+// function to get a report from the hardware monitor instance, returns the buffer size it needs
+int GetReport(void* handle, char* buffer, int bufferSize)
 {
     // cast the handle back to the original gcroot
     gcroot<HardwareMonitorWrapper^>* wrapperHandle = static_cast<gcroot<HardwareMonitorWrapper^>*>(handle);
 
     // call the GetReport method on the instance
-    (*wrapperHandle)->GetReport(buffer, bufferSize);
+    return (*wrapperHandle)->GetReport(buffer, bufferSize);
+}
+
+// function to reset the minimum and maximum values of a hardware's sensors
+void ResetMinMax(void* handle, const char* hardwareIdentifier)
+{
+    // cast the handle back to the original gcroot
+    gcroot<HardwareMonitorWrapper^>* wrapperHandle = static_cast<gcroot<HardwareMonitorWrapper^>*>(handle);
+
+    // call the ResetMinMax method on the instance
+    (*wrapperHandle)->ResetMinMax(hardwareIdentifier);
 }
 
 // function to destroy the hardware monitor instance and clean up memory
@@ -32,13 +32,33 @@ namespace ManagedLibreHardwareMonitorWrapper
         }
 
         // method to get a report from the hardware monitor and store it in a buffer
-        void GetReport(char* buffer, int bufferSize)
+        // returns the size the buffer needs including the terminator, the buffer is left empty if it is too small
+        int GetReport(char* buffer, int bufferSize)
         {
             String^ report = _hardwareMonitor->GetReport();
             pin_ptr<const wchar_t> wstr = PtrToStringChars(report);
-            size_t result = wcstombs(buffer, wstr, bufferSize);
-            if (result == static_cast<size_t>(-1))
+
+            size_t length = wcstombs(nullptr, wstr, 0);
+            if (length == static_cast<size_t>(-1))
+            {
+                buffer[0] = '\0';
+                return 0;
+            }
+
+            if (length + 1 > static_cast<size_t>(bufferSize))
+            {
                 buffer[0] = '\0';
+                return static_cast<int>(length + 1);
+            }
+
+            wcstombs(buffer, wstr, bufferSize);
+            return static_cast<int>(length + 1);
+        }
+
+        // method to reset the minimum and maximum values of a hardware's sensors
+        void ResetMinMax(const char* hardwareIdentifier)
+        {
+            _hardwareMonitor->ResetMinMax(gcnew String(hardwareIdentifier));
         }
     };
 }
@@ -46,5 +66,6 @@ namespace ManagedLibreHardwareMonitorWrapper
 // declare C-style functions for interacting with the wrapper
 extern "C" __declspec(dllexport) void* CreateHardwareMonitor();
 extern "C" __declspec(dllexport) void UpdateHardwareMonitor(void* instance);
-extern "C" __declspec(dllexport) void GetReport(void* instance, char* buffer, int bufferSize);
+extern "C" __declspec(dllexport) int GetReport(void* instance, char* buffer, int bufferSize);
+extern "C" __declspec(dllexport) void ResetMinMax(void* instance, const char* hardwareIdentifier);
 extern "C" __declspec(dllexport) void DestroyHardwareMonitor(void* instance);
\ No newline at end of file
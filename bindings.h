@@ -6,7 +6,8 @@ extern "C" {
 
 void* CreateHardwareMonitor();
 void UpdateHardwareMonitor(void* handle);
-void GetReport(void* handle, char* buffer, int bufferSize);
+int GetReport(void* handle, char* buffer, int bufferSize);
+void ResetMinMax(void* handle, const char* hardwareIdentifier);
 void DestroyHardwareMonitor(void* handle);
 
 #ifdef __cplusplus
@@ -0,0 +1,221 @@
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::process::Command;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+
+use crate::alert::AlertEvent;
+use crate::http;
+
+type Result<T> = std::result::Result<T, Error>;
+
+// used when a log action has no path, in the working directory, relative paths in rules are too
+const DEFAULT_LOG: &str = "alerts.log";
+
+// how long an action may take before it is abandoned
+const DEFAULT_TIMEOUT_SECONDS: u64 = 10;
+
+// stops commands from opening a console window
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+// error type
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Timeout(u64),
+    // the command ran but did not succeed, contains the exit code if there is one
+    Exit(Option<i32>),
+    Http(http::Error),
+    // the webhook responded with something other than a success status
+    Status(String),
+}
+
+// implement from for io error
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// implement from for serde json error
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+// implement from for http error
+impl From<http::Error> for Error {
+    fn from(error: http::Error) -> Self {
+        Self::Http(error)
+    }
+}
+
+// implement display for error
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Json(error) => write!(f, "JSON error: {}", error),
+            Self::Timeout(seconds) => write!(f, "timed out after {} s", seconds),
+            Self::Exit(Some(code)) => write!(f, "command exited with code {}", code),
+            Self::Exit(None) => write!(f, "command was terminated"),
+            Self::Http(error) => write!(f, "{}", error),
+            Self::Status(status) => write!(f, "webhook responded with {}", status),
+        }
+    }
+}
+
+// what happens when an alert fires or clears
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum Action {
+    // run a command with cmd, the alert is passed in ALERT_* environment variables
+    Command {
+        command: String,
+        #[serde(default = "default_timeout")]
+        timeout_seconds: u64,
+    },
+    // append the alert as a line of json
+    Log {
+        #[serde(default = "default_log")]
+        path: String,
+    },
+    // post the alert as json, only plain http is supported
+    Webhook {
+        url: String,
+        #[serde(default = "default_timeout")]
+        timeout_seconds: u64,
+    },
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT_SECONDS
+}
+
+fn default_log() -> String {
+    String::from(DEFAULT_LOG)
+}
+
+impl Action {
+    fn timeout_seconds(&self) -> u64 {
+        match self {
+            Self::Command {
+                timeout_seconds, ..
+            }
+            | Self::Webhook {
+                timeout_seconds, ..
+            } => *timeout_seconds,
+            Self::Log { .. } => DEFAULT_TIMEOUT_SECONDS,
+        }
+    }
+
+    // a short description for error messages
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Command { .. } => "command",
+            Self::Log { .. } => "log",
+            Self::Webhook { .. } => "webhook",
+        }
+    }
+}
+
+// asynchronously run an action for an alert, giving up once its timeout has passed
+pub(crate) async fn run(action: Action, event: AlertEvent) -> std::result::Result<(), String> {
+    let seconds = action.timeout_seconds();
+
+    let result = match timeout(Duration::from_secs(seconds), execute(&action, &event)).await {
+        Ok(result) => result,
+        Err(_) => Err(Error::Timeout(seconds)),
+    };
+
+    result.map_err(|error| format!("{} {} failed: {}", event.rule, action.kind(), error))
+}
+
+async fn execute(action: &Action, event: &AlertEvent) -> Result<()> {
+    match action {
+        Action::Command { command, .. } => {
+            // the process is killed if the timeout drops it
+            let status = Command::new("cmd")
+                .arg("/C")
+                .arg(command)
+                .envs(environment(event))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .creation_flags(CREATE_NO_WINDOW)
+                .kill_on_drop(true)
+                .status()
+                .await?;
+
+            if status.success() {
+                Ok(())
+            } else {
+                Err(Error::Exit(status.code()))
+            }
+        }
+        Action::Log { path } => {
+            let path = path.clone();
+            let line = format!("{}\n", serde_json::to_string(&payload(event))?);
+
+            spawn_blocking(move || {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                file.write_all(line.as_bytes())?;
+                Ok(())
+            })
+            .await
+            .unwrap()
+        }
+        Action::Webhook { url, .. } => post(url, &serde_json::to_vec(&payload(event))?).await,
+    }
+}
+
+// the alert as environment variables for commands
+fn environment(event: &AlertEvent) -> Vec<(&'static str, String)> {
+    vec![
+        ("ALERT_STATE", event.transition.to_string()),
+        ("ALERT_RULE", event.rule.clone()),
+        ("ALERT_SEVERITY", event.severity.to_string()),
+        ("ALERT_HARDWARE", event.hardware.clone()),
+        ("ALERT_HARDWARE_ID", event.hardware_identifier.clone()),
+        ("ALERT_SENSOR", event.sensor.clone()),
+        ("ALERT_SENSOR_ID", event.sensor_identifier.clone()),
+        ("ALERT_VALUE", event.value.to_string()),
+        ("ALERT_THRESHOLD", event.threshold.to_string()),
+        ("ALERT_TIMESTAMP", event.timestamp.to_rfc3339()),
+    ]
+}
+
+// the alert as json for the log and webhooks
+fn payload(event: &AlertEvent) -> serde_json::Value {
+    json!({
+        "timestamp": event.timestamp.to_rfc3339(),
+        "state": event.transition.to_string(),
+        "rule": event.rule,
+        "severity": event.severity.to_string(),
+        "hardware": event.hardware,
+        "hardware_identifier": event.hardware_identifier,
+        "sensor": event.sensor,
+        "sensor_identifier": event.sensor_identifier,
+        "value": event.value,
+        "threshold": event.threshold,
+    })
+}
+
+// anything but a success status counts as failed, the body is not needed
+async fn post(url: &str, body: &[u8]) -> Result<()> {
+    let response = http::post(url, "application/json", &[], body).await?;
+
+    if response.success() {
+        Ok(())
+    } else {
+        Err(Error::Status(response.status.to_string()))
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alert::action::Action;
+use crate::system::{Hardware, Report, Sensor};
+
+pub(crate) mod action;
+
+// how bad an alert is, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Info => "info",
+                Self::Warning => "warning",
+                Self::Critical => "critical",
+            }
+        )
+    }
+}
+
+// which side of the threshold raises the alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Condition {
+    Above,
+    Below,
+}
+
+// a user defined alert, checked against every sensor that matches it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Rule {
+    pub(crate) name: String,
+    pub(crate) enabled: bool,
+    // patterns where * matches any text and ? any character, e.g. Gpu* or CPU Core #*
+    pub(crate) hardware: String,
+    pub(crate) sensor_type: String,
+    pub(crate) sensor: String,
+    pub(crate) condition: Condition,
+    pub(crate) threshold: f32,
+    // compare 100 minus the reading, e.g. free space from the used space percentage
+    pub(crate) complement: bool,
+    // how long the threshold has to be crossed before the alert is raised
+    pub(crate) duration_seconds: u32,
+    // how far back past the threshold the reading has to go before the alert clears
+    pub(crate) hysteresis: f32,
+    // the least time between two alerts for the same sensor
+    pub(crate) cooldown_seconds: u32,
+    pub(crate) severity: Severity,
+    // run when the alert fires and when it clears
+    pub(crate) actions: Vec<Action>,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            hardware: String::from("*"),
+            sensor_type: String::from("*"),
+            sensor: String::from("*"),
+            condition: Condition::Above,
+            threshold: 0_f32,
+            complement: false,
+            duration_seconds: 0,
+            hysteresis: 0_f32,
+            cooldown_seconds: 60,
+            severity: Severity::Warning,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl Rule {
+    fn matches(&self, hardware: &Hardware, sensor: &Sensor) -> bool {
+        self.enabled
+            && wildcard(&self.hardware, &hardware.hardware_type.to_string())
+            && wildcard(&self.sensor_type, &sensor.sensor_type.to_string())
+            && wildcard(&self.sensor, &sensor.name)
+    }
+
+    // the value compared against the threshold
+    fn value(&self, sensor: &Sensor) -> f32 {
+        if self.complement {
+            100_f32 - sensor.value
+        } else {
+            sensor.value
+        }
+    }
+
+    fn breached(&self, value: f32) -> bool {
+        match self.condition {
+            Condition::Above => value > self.threshold,
+            Condition::Below => value < self.threshold,
+        }
+    }
+
+    // past the threshold by at least the hysteresis in the other direction
+    fn recovered(&self, value: f32) -> bool {
+        match self.condition {
+            Condition::Above => value <= self.threshold - self.hysteresis,
+            Condition::Below => value >= self.threshold + self.hysteresis,
+        }
+    }
+}
+
+// the rules created with a new config
+pub(crate) fn default_rules() -> Vec<Rule> {
+    vec![
+        Rule {
+            name: String::from("CPU core temperature"),
+            hardware: String::from("Cpu"),
+            sensor_type: String::from("Temperature"),
+            sensor: String::from("CPU Core #*"),
+            threshold: 95_f32,
+            duration_seconds: 10,
+            hysteresis: 5_f32,
+            severity: Severity::Critical,
+            ..Rule::default()
+        },
+        Rule {
+            name: String::from("GPU hot spot temperature"),
+            hardware: String::from("Gpu*"),
+            sensor_type: String::from("Temperature"),
+            sensor: String::from("GPU Hot Spot"),
+            threshold: 100_f32,
+            hysteresis: 5_f32,
+            severity: Severity::Critical,
+            ..Rule::default()
+        },
+        Rule {
+            name: String::from("Disk free space"),
+            hardware: String::from("Storage"),
+            sensor_type: String::from("Load"),
+            sensor: String::from("Used Space"),
+            condition: Condition::Below,
+            threshold: 5_f32,
+            complement: true,
+            hysteresis: 1_f32,
+            cooldown_seconds: 3600,
+            ..Rule::default()
+        },
+        Rule {
+            name: String::from("Network utilization"),
+            hardware: String::from("Network"),
+            sensor_type: String::from("Load"),
+            sensor: String::from("Network Utilization"),
+            threshold: 90_f32,
+            duration_seconds: 60,
+            hysteresis: 10_f32,
+            severity: Severity::Info,
+            ..Rule::default()
+        },
+    ]
+}
+
+// whether the alert was raised or cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Transition {
+    Fired,
+    Cleared,
+}
+
+// passed to alert actions
+impl Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Fired => "fired",
+                Self::Cleared => "cleared",
+            }
+        )
+    }
+}
+
+// a change in the state of an alert, for anything which acts on alerts
+#[derive(Debug, Clone)]
+pub(crate) struct AlertEvent {
+    pub(crate) transition: Transition,
+    // the position of the rule in the config, names may repeat
+    pub(crate) rule_index: usize,
+    pub(crate) rule: String,
+    pub(crate) severity: Severity,
+    pub(crate) hardware: String,
+    pub(crate) hardware_identifier: String,
+    pub(crate) sensor: String,
+    pub(crate) sensor_identifier: String,
+    // the value compared against the threshold
+    pub(crate) value: f32,
+    pub(crate) threshold: f32,
+    pub(crate) timestamp: DateTime<Local>,
+}
+
+// the state of one rule for one sensor
+#[derive(Debug, Clone, Default)]
+struct AlertState {
+    // when the reading first crossed the threshold, cleared when it goes back
+    breached_since: Option<DateTime<Local>>,
+    // when the alert was last raised
+    fired_at: Option<DateTime<Local>>,
+    active: bool,
+    severity: Option<Severity>,
+    // the hardware the sensor belongs to, for highlighting it
+    hardware_identifier: String,
+}
+
+// evaluates the rules against each report and keeps track of the active alerts
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Alerts {
+    // keyed by the position of the rule and the sensor identifier, names may repeat
+    states: HashMap<(usize, String), AlertState>,
+}
+
+impl Alerts {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // check every rule against the report, returns the alerts which were raised or cleared
+    pub(crate) fn evaluate(&mut self, rules: &[Rule], report: &Report) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        let now = report.timestamp;
+
+        for (rule_index, rule) in rules.iter().enumerate() {
+            for hardware in &report.hardware {
+                for sensor in &hardware.sensors {
+                    if !rule.matches(hardware, sensor) {
+                        continue;
+                    }
+
+                    let value = rule.value(sensor);
+                    let state = self
+                        .states
+                        .entry((rule_index, sensor.identifier.clone()))
+                        .or_default();
+
+                    state.hardware_identifier = hardware.identifier.clone();
+
+                    let transition = if state.active {
+                        if rule.recovered(value) {
+                            state.active = false;
+                            state.breached_since = None;
+                            Some(Transition::Cleared)
+                        } else {
+                            None
+                        }
+                    } else if rule.breached(value) {
+                        let since = *state.breached_since.get_or_insert(now);
+                        let held = now - since >= Duration::seconds(rule.duration_seconds as i64);
+                        let rested = state.fired_at.map_or(true, |fired_at| {
+                            now - fired_at >= Duration::seconds(rule.cooldown_seconds as i64)
+                        });
+
+                        if held && rested {
+                            state.active = true;
+                            state.fired_at = Some(now);
+                            Some(Transition::Fired)
+                        } else {
+                            None
+                        }
+                    } else {
+                        state.breached_since = None;
+                        None
+                    };
+
+                    state.severity = state.active.then_some(rule.severity);
+
+                    if let Some(transition) = transition {
+                        events.push(AlertEvent {
+                            transition,
+                            rule_index,
+                            rule: rule.name.clone(),
+                            severity: rule.severity,
+                            hardware: hardware.name.clone(),
+                            hardware_identifier: hardware.identifier.clone(),
+                            sensor: sensor.name.clone(),
+                            sensor_identifier: sensor.identifier.clone(),
+                            value,
+                            threshold: rule.threshold,
+                            timestamp: now,
+                        });
+                    }
+                }
+            }
+        }
+
+        events
+    }
+
+    // the most severe active alert on the hardware
+    pub(crate) fn severity(&self, hardware_identifier: &str) -> Option<Severity> {
+        self.states
+            .values()
+            .filter(|state| state.hardware_identifier == hardware_identifier)
+            .filter_map(|state| state.severity)
+            .max()
+    }
+}
+
+// match text against a pattern where * matches any text and ? any one character
+pub(crate) fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // the position after the last * and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    let (mut p, mut t) = (0, 0);
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(character) if *character == '?' || *character == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // let the last * swallow one more character
+                Some((after, start)) => {
+                    backtrack = Some((after, start + 1));
+                    p = after;
+                    t = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|character| *character == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::{HardwareType, SensorType};
+
+    // a report with one cpu core temperature, taken the given number of seconds after start
+    fn report(start: DateTime<Local>, seconds: i64, value: f32) -> Report {
+        Report {
+            timestamp: start + Duration::seconds(seconds),
+            hardware: vec![Hardware {
+                hardware_type: HardwareType::Cpu,
+                identifier: String::from("/amdcpu/0"),
+                name: String::from("Ryzen 7"),
+                sensors: vec![Sensor {
+                    sensor_type: SensorType::Temperature,
+                    identifier: String::from("/amdcpu/0/temperature/2"),
+                    name: String::from("CPU Core #1"),
+                    index: 0,
+                    value,
+                    max: value,
+                    min: value,
+                }],
+            }],
+        }
+    }
+
+    fn rule() -> Rule {
+        Rule {
+            name: String::from("hot"),
+            hardware: String::from("Cpu"),
+            sensor_type: String::from("Temperature"),
+            sensor: String::from("CPU Core #*"),
+            threshold: 90_f32,
+            hysteresis: 5_f32,
+            cooldown_seconds: 0,
+            ..Rule::default()
+        }
+    }
+
+    // feed the readings one second apart, returns the second and transition of every event
+    fn run(rules: &[Rule], readings: &[f32]) -> Vec<(i64, Transition)> {
+        let start = Local::now();
+        let mut alerts = Alerts::new();
+
+        readings
+            .iter()
+            .enumerate()
+            .flat_map(|(second, value)| {
+                alerts
+                    .evaluate(rules, &report(start, second as i64, *value))
+                    .into_iter()
+                    .map(move |event| (second as i64, event.transition))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fires_above_and_clears_past_the_hysteresis() {
+        let events = run(&[rule()], &[80.0, 91.0, 92.0, 88.0, 86.0, 85.0, 80.0]);
+
+        // 88 and 86 are still within the hysteresis, 85 is not
+        assert_eq!(events, [(1, Transition::Fired), (5, Transition::Cleared)]);
+    }
+
+    #[test]
+    fn waits_for_the_duration() {
+        let rule = Rule {
+            duration_seconds: 2,
+            ..rule()
+        };
+        let events = run(&[rule], &[91.0, 91.0, 80.0, 91.0, 91.0, 91.0]);
+
+        // the first breach ends before it has lasted two seconds
+        assert_eq!(events, [(5, Transition::Fired)]);
+    }
+
+    #[test]
+    fn cooldown_holds_back_the_next_alert() {
+        let rule = Rule {
+            cooldown_seconds: 3,
+            ..rule()
+        };
+        let events = run(&[rule], &[91.0, 80.0, 91.0, 91.0, 80.0, 91.0]);
+
+        assert_eq!(
+            events,
+            [
+                (0, Transition::Fired),
+                (1, Transition::Cleared),
+                (3, Transition::Fired),
+                (4, Transition::Cleared),
+            ]
+        );
+    }
+
+    #[test]
+    fn below_with_complement() {
+        // used space of 96% is 4% free, below the 5% threshold
+        let rule = Rule {
+            condition: Condition::Below,
+            threshold: 5_f32,
+            complement: true,
+            hysteresis: 1_f32,
+            ..rule()
+        };
+        let events = run(&[rule], &[90.0, 96.0, 94.5, 93.0]);
+
+        assert_eq!(events, [(1, Transition::Fired), (3, Transition::Cleared)]);
+    }
+
+    #[test]
+    fn rules_only_match_their_sensors() {
+        let other = Rule {
+            sensor: String::from("GPU*"),
+            ..rule()
+        };
+        assert!(run(&[other], &[99.0]).is_empty());
+
+        let disabled = Rule {
+            enabled: false,
+            ..rule()
+        };
+        assert!(run(&[disabled], &[99.0]).is_empty());
+    }
+
+    #[test]
+    fn rules_with_the_same_name_keep_their_own_state() {
+        let slow = Rule {
+            duration_seconds: 1,
+            ..rule()
+        };
+        let events = run(&[rule(), slow], &[91.0, 91.0]);
+
+        assert_eq!(events, [(0, Transition::Fired), (1, Transition::Fired)]);
+    }
+
+    #[test]
+    fn severity_follows_the_active_alert() {
+        let start = Local::now();
+        let mut alerts = Alerts::new();
+
+        alerts.evaluate(&[rule()], &report(start, 0, 95.0));
+        assert_eq!(alerts.severity("/amdcpu/0"), Some(Severity::Warning));
+
+        alerts.evaluate(&[rule()], &report(start, 1, 80.0));
+        assert_eq!(alerts.severity("/amdcpu/0"), None);
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard("CPU Core #*", "CPU Core #12"));
+        assert!(wildcard("Gpu*", "GpuNvidia"));
+        assert!(wildcard("CPU Core #?", "CPU Core #1"));
+        assert!(!wildcard("CPU Core #?", "CPU Core #12"));
+        assert!(wildcard("*Hot*", "GPU Hot Spot"));
+        assert!(wildcard("*", ""));
+        assert!(!wildcard("Cpu", "Cpu Package"));
+    }
+}
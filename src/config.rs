@@ -6,6 +6,14 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::alert::{default_rules, Rule};
+use crate::fleet::FleetConfig;
+use crate::mqtt::MqttConfig;
+use crate::remote::RemoteConfig;
+use crate::server::ServerConfig;
+use crate::sink::SinkConfig;
+use crate::system::statistics::StatisticsWindow;
+use crate::ui::export::{ImageFormat, ImageSize};
 use crate::ui::Theme;
 
 type Result<T> = std::result::Result<T, Error>;
@@ -41,12 +49,32 @@ impl Display for Error {
     }
 }
 
-// configuration options
+// configuration options, missing options fall back to the defaults so older files still load
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub(crate) struct Config {
     pub(crate) theme: Theme,
     pub(crate) celsius: bool,
     pub(crate) visibility: HashMap<String, bool>,
+    // how far back the statistics panels look
+    pub(crate) statistics_window: StatisticsWindow,
+    // the file type and resolution graphs are saved with
+    pub(crate) export_format: ImageFormat,
+    pub(crate) export_size: ImageSize,
+    // user defined alert rules, checked after every update
+    pub(crate) alerts: Vec<Rule>,
+    // learn the normal range of each sensor and flag readings outside it
+    pub(crate) anomaly_detection: bool,
+    // the local http server other tools read the sensors from
+    pub(crate) server: ServerConfig,
+    // publish the sensors to an mqtt broker, e.g. for home assistant
+    pub(crate) mqtt: MqttConfig,
+    // write the sensors to influxdb, graphite, statsd or an opentelemetry collector
+    pub(crate) sinks: SinkConfig,
+    // show another instance's sensors instead of this machine's
+    pub(crate) remote: RemoteConfig,
+    // other instances shown side by side on the fleet page
+    pub(crate) fleet: FleetConfig,
 }
 
 // default options
@@ -56,6 +84,16 @@ impl Default for Config {
             theme: Theme::System,
             celsius: true,
             visibility: Default::default(),
+            statistics_window: StatisticsWindow::TenMinutes,
+            export_format: ImageFormat::Png,
+            export_size: ImageSize::FullHd,
+            alerts: default_rules(),
+            anomaly_detection: false,
+            server: ServerConfig::default(),
+            mqtt: MqttConfig::default(),
+            sinks: SinkConfig::default(),
+            remote: RemoteConfig::default(),
+            fleet: FleetConfig::default(),
         }
     }
 }
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use iced::futures::stream::FuturesUnordered;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::alert::Severity;
+use crate::remote;
+use crate::system::Report;
+
+// the machines on the fleet page, each running corroded monitor with its stream enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FleetConfig {
+    pub(crate) hosts: Vec<FleetHost>,
+    // how often every host is polled while the page is open
+    pub(crate) interval_seconds: u64,
+    // used for every host without thresholds of its own
+    pub(crate) thresholds: Thresholds,
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self {
+            hosts: Vec::new(),
+            interval_seconds: 5,
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FleetHost {
+    // shown on its tile, the address when empty
+    pub(crate) name: String,
+    // the address its server listens on, e.g. 192.168.1.20:9184
+    pub(crate) address: String,
+    // the token set in its config, if any
+    pub(crate) token: Option<String>,
+    pub(crate) thresholds: Option<Thresholds>,
+}
+
+impl FleetHost {
+    pub(crate) fn label(&self) -> &str {
+        if self.name.is_empty() {
+            &self.address
+        } else {
+            &self.name
+        }
+    }
+}
+
+// when a tile turns yellow or red, temperatures in celsius and loads in percent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Thresholds {
+    pub(crate) temperature_warning: f32,
+    pub(crate) temperature_critical: f32,
+    pub(crate) load_warning: f32,
+    pub(crate) load_critical: f32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            temperature_warning: 75.0,
+            temperature_critical: 90.0,
+            load_warning: 80.0,
+            load_critical: 95.0,
+        }
+    }
+}
+
+impl Thresholds {
+    // the worst of the temperatures and loads
+    pub(crate) fn severity(&self, temperatures: &[f32], loads: &[f32]) -> Option<Severity> {
+        let exceeds =
+            |values: &[f32], threshold: f32| values.iter().any(|value| *value >= threshold);
+
+        if exceeds(temperatures, self.temperature_critical) || exceeds(loads, self.load_critical) {
+            Some(Severity::Critical)
+        } else if exceeds(temperatures, self.temperature_warning)
+            || exceeds(loads, self.load_warning)
+        {
+            Some(Severity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+// the result of polling a host, by its address
+#[derive(Debug, Clone)]
+pub(crate) struct Event {
+    pub(crate) address: String,
+    pub(crate) result: Result<Arc<Report>, String>,
+}
+
+// poll every host at the interval, each on its own so an unreachable one does not hold up the rest
+pub(crate) fn poll(config: FleetConfig) -> Subscription<Event> {
+    // new hosts or a new interval start polling again
+    let id = (
+        config
+            .hosts
+            .iter()
+            .map(|host| (host.address.clone(), host.token.clone()))
+            .collect::<Vec<_>>(),
+        config.interval_seconds,
+    );
+
+    subscription::channel(id, 16, |mut output| async move {
+        let interval = Duration::from_secs(config.interval_seconds.max(1));
+
+        let mut polls = config
+            .hosts
+            .into_iter()
+            .map(|host| poll_host(host, Duration::ZERO))
+            .collect::<FuturesUnordered<_>>();
+
+        loop {
+            match polls.next().await {
+                // sent as soon as it arrives, then the host waits out the interval again
+                Some((host, event)) => {
+                    let _ = output.send(event).await;
+                    polls.push(poll_host(host, interval));
+                }
+                None => sleep(interval).await,
+            }
+        }
+    })
+}
+
+// poll the host once the delay has passed, the host is handed back for the next poll
+async fn poll_host(host: FleetHost, delay: Duration) -> (FleetHost, Event) {
+    sleep(delay).await;
+
+    let event = Event {
+        address: host.address.clone(),
+        result: remote::fetch(host.address.clone(), host.token.clone())
+            .await
+            .map(Arc::new),
+    };
+
+    (host, event)
+}
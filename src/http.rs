@@ -0,0 +1,228 @@
+use std::fmt::Display;
+use std::io;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+// a minimal http/1.1 client for webhooks and time series databases on this machine or the local network
+
+// error type
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io(io::Error),
+    // the url cannot be posted to, contains the reason
+    Url(String),
+}
+
+// implement from for io error
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// implement display for error
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Url(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+// the parts of a url needed to post to it
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Url {
+    // sent as the Host header, as written in the url
+    pub(crate) host: String,
+    // what is connected to, the host with the port
+    pub(crate) address: String,
+    // the path and query
+    pub(crate) path: String,
+}
+
+impl Url {
+    // only plain http, there is no tls
+    pub(crate) fn parse(url: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::Url(format!("{}: {}", reason, url));
+
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| invalid("expected http://host[:port]/path"))?;
+
+        if scheme.eq_ignore_ascii_case("https") {
+            return Err(invalid("https is not supported, use http"));
+        } else if !scheme.eq_ignore_ascii_case("http") {
+            return Err(invalid("expected http://host[:port]/path"));
+        }
+
+        let (host, path) = match rest.find(['/', '?']) {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+
+        // ipv6 addresses are in brackets so their colons are not taken for the port, e.g. [::1]:8086
+        let (name, port) = match host.strip_prefix('[') {
+            Some(bracketed) => {
+                let (name, rest) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed bracket in the host"))?;
+
+                match rest.strip_prefix(':') {
+                    Some(port) => (format!("[{}]", name), Some(port)),
+                    None if rest.is_empty() => (format!("[{}]", name), None),
+                    None => return Err(invalid("unexpected text after the host")),
+                }
+            }
+            None => match host.split_once(':') {
+                Some((name, port)) => (name.to_string(), Some(port)),
+                None => (host.to_string(), None),
+            },
+        };
+
+        if name.is_empty() || name == "[]" {
+            return Err(invalid("missing host"));
+        }
+
+        let port = match port {
+            Some(port) => port.parse::<u16>().map_err(|_| invalid("invalid port"))?,
+            None => 80,
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            address: format!("{}:{}", name, port),
+            path: match path.starts_with('?') {
+                true => format!("/{}", path),
+                false => path.to_string(),
+            },
+        })
+    }
+}
+
+// what the server answered
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+}
+
+impl Response {
+    pub(crate) fn success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+// post the body and wait for the whole response, the connection is closed after it
+pub(crate) async fn post(
+    url: &str,
+    content_type: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> Result<Response, Error> {
+    let url = Url::parse(url)?;
+
+    let mut head = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        url.path,
+        url.host,
+        content_type,
+        body.len()
+    );
+
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    head.push_str("\r\n");
+
+    let mut stream = TcpStream::connect(&url.address).await?;
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let response = String::from_utf8_lossy(&response);
+
+    // e.g. HTTP/1.1 204 No Content
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid http response"))?;
+
+    Ok(Response {
+        status,
+        body: response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.trim().to_string())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> (String, String, String) {
+        let url = Url::parse(url).unwrap();
+        (url.host, url.address, url.path)
+    }
+
+    #[test]
+    fn default_port() {
+        assert_eq!(
+            parse("http://influx.local/write?db=sensors"),
+            (
+                String::from("influx.local"),
+                String::from("influx.local:80"),
+                String::from("/write?db=sensors")
+            )
+        );
+    }
+
+    #[test]
+    fn given_port_and_no_path() {
+        assert_eq!(
+            parse("http://127.0.0.1:8086"),
+            (
+                String::from("127.0.0.1:8086"),
+                String::from("127.0.0.1:8086"),
+                String::from("/")
+            )
+        );
+        assert_eq!(parse("HTTP://host?a=1").2, "/?a=1");
+    }
+
+    #[test]
+    fn ipv6() {
+        assert_eq!(parse("http://[::1]/hook").1, "[::1]:80");
+        assert_eq!(
+            parse("http://[fe80::1]:4318/v1/metrics"),
+            (
+                String::from("[fe80::1]:4318"),
+                String::from("[fe80::1]:4318"),
+                String::from("/v1/metrics")
+            )
+        );
+    }
+
+    #[test]
+    fn rejected() {
+        for url in [
+            "https://example.com/hook",
+            "ftp://example.com",
+            "example.com/hook",
+            "http:///path",
+            "http://host:port/",
+            "http://host:99999/",
+            "http://[::1/",
+            "http://[::1]x/",
+            "http://::1/",
+        ] {
+            assert!(Url::parse(url).is_err(), "{}", url);
+        }
+    }
+}
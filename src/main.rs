@@ -12,7 +12,15 @@ compile_error!("This application only supports Windows.");
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+mod alert;
 mod config;
+mod fleet;
+mod http;
+mod mqtt;
+mod recorder;
+mod remote;
+mod server;
+mod sink;
 mod system;
 mod ui;
 
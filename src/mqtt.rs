@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+
+use crate::system::{Hardware, Report, Sensor, SensorType};
+
+// how long to wait before reconnecting after the broker drops the connection
+const RECONNECT_SECONDS: u64 = 5;
+
+// messages queued for the broker, enough for every sensor and its discovery config
+const QUEUE_CAPACITY: usize = 4096;
+
+// the latest report, published on the interval
+type Reports = watch::Receiver<Option<Arc<Report>>>;
+
+// where to publish and how often
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct MqttConfig {
+    pub(crate) enabled: bool,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+    // also names this machine in topics and home assistant, the computer name when empty
+    pub(crate) client_id: String,
+    // sensors are published to {topic_prefix}/{client_id}/{sensor identifier}
+    pub(crate) topic_prefix: String,
+    pub(crate) interval_seconds: u64,
+    // publish home assistant discovery configs so every sensor shows up on its own
+    pub(crate) discovery: bool,
+    pub(crate) discovery_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::from("localhost"),
+            port: 1883,
+            username: None,
+            password: None,
+            client_id: String::new(),
+            topic_prefix: String::from("corroded_monitor"),
+            interval_seconds: 10,
+            discovery: false,
+            discovery_prefix: String::from("homeassistant"),
+        }
+    }
+}
+
+impl MqttConfig {
+    fn client_id(&self) -> String {
+        if !self.client_id.is_empty() {
+            return self.client_id.clone();
+        }
+
+        std::env::var("COMPUTERNAME")
+            .map(|name| sanitize(&name).to_lowercase())
+            .unwrap_or_else(|_| String::from("corroded_monitor"))
+    }
+
+    fn topic(&self, client_id: &str, identifier: &str) -> String {
+        format!("{}/{}{}", self.topic_prefix, client_id, identifier)
+    }
+
+    // online or offline, set by the last will when the app disconnects
+    fn availability_topic(&self, client_id: &str) -> String {
+        format!("{}/{}/status", self.topic_prefix, client_id)
+    }
+}
+
+// publishes readings until dropped
+#[derive(Debug)]
+pub(crate) struct Publisher {
+    task: JoinHandle<()>,
+    status: watch::Receiver<String>,
+}
+
+// asynchronously start connecting, readings are published whenever the broker is reachable
+pub(crate) async fn start(config: MqttConfig, reports: Reports) -> Arc<Publisher> {
+    let (status, receiver) = watch::channel(format!("Connecting to {}", config.host));
+
+    Arc::new(Publisher {
+        task: tokio::spawn(run(config, reports, status)),
+        status: receiver,
+    })
+}
+
+impl Publisher {
+    // the state of the connection, shown in settings
+    pub(crate) fn status(&self) -> String {
+        self.status.borrow().clone()
+    }
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(config: MqttConfig, reports: Reports, status: watch::Sender<String>) {
+    let client_id = config.client_id();
+    let availability = config.availability_topic(&client_id);
+
+    let mut options = MqttOptions::new(client_id.clone(), config.host.clone(), config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_max_packet_size(64 * 1024, 64 * 1024);
+    options.set_last_will(LastWill::new(
+        availability.clone(),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, QUEUE_CAPACITY);
+
+    let mut ticker = interval(Duration::from_secs(config.interval_seconds.max(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut connected = false;
+    // sensors whose discovery config was sent on this connection
+    let mut announced = HashSet::new();
+
+    loop {
+        tokio::select! {
+            event = eventloop.poll() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    connected = true;
+                    announced.clear();
+                    let _ = client.try_publish(&availability, QoS::AtLeastOnce, true, "online");
+                    status.send_replace(format!(
+                        "Publishing to {}:{} every {} s",
+                        config.host, config.port, config.interval_seconds
+                    ));
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    connected = false;
+                    status.send_replace(format!("Disconnected: {}", error));
+                    // polling again reconnects
+                    sleep(Duration::from_secs(RECONNECT_SECONDS)).await;
+                }
+            },
+            _ = ticker.tick() => {
+                // queued readings would be stale by the time the broker is back
+                if !connected {
+                    continue;
+                }
+
+                let latest = reports.borrow().clone();
+
+                if let Some(report) = latest {
+                    publish(&client, &config, &client_id, &report, &mut announced);
+                }
+            }
+        }
+    }
+}
+
+// queue every sensor of the report, and its discovery config the first time it is seen
+fn publish(
+    client: &AsyncClient,
+    config: &MqttConfig,
+    client_id: &str,
+    report: &Report,
+    announced: &mut HashSet<String>,
+) {
+    for hardware in &report.hardware {
+        for sensor in &hardware.sensors {
+            let topic = config.topic(client_id, &sensor.identifier);
+
+            if config.discovery && !announced.contains(&sensor.identifier) {
+                let (discovery_topic, payload) = discovery(config, client_id, hardware, sensor);
+
+                // retained so home assistant finds the sensors after it restarts
+                if client
+                    .try_publish(discovery_topic, QoS::AtLeastOnce, true, payload.to_string())
+                    .is_ok()
+                {
+                    announced.insert(sensor.identifier.clone());
+                }
+            }
+
+            let payload = json!({
+                "value": sensor.value,
+                "unit": sensor.sensor_type.unit(),
+                "name": sensor.name,
+                "type": sensor.sensor_type.to_string(),
+                "hardware": hardware.name,
+                "timestamp": report.timestamp.to_rfc3339(),
+            });
+
+            // the queue is full when the broker falls behind, the next interval tries again
+            let _ = client.try_publish(topic, QoS::AtMostOnce, false, payload.to_string());
+        }
+    }
+}
+
+// the home assistant discovery topic and config for a sensor, each hardware is a device
+fn discovery(
+    config: &MqttConfig,
+    client_id: &str,
+    hardware: &Hardware,
+    sensor: &Sensor,
+) -> (String, serde_json::Value) {
+    let object_id = format!("{}{}", client_id, sanitize(&sensor.identifier));
+    let topic = format!(
+        "{}/sensor/{}/{}/config",
+        config.discovery_prefix, client_id, object_id
+    );
+
+    let mut payload = json!({
+        "name": sensor.name,
+        "unique_id": object_id,
+        "object_id": object_id,
+        "state_topic": config.topic(client_id, &sensor.identifier),
+        "value_template": "{{ value_json.value }}",
+        "state_class": "measurement",
+        "availability_topic": config.availability_topic(client_id),
+        "device": {
+            "identifiers": [format!("{}{}", client_id, sanitize(&hardware.identifier))],
+            "name": format!("{} {}", client_id, hardware.name),
+            "model": hardware.name,
+            "manufacturer": "Corroded Monitor",
+        },
+    });
+
+    if !sensor.sensor_type.unit().is_empty() {
+        payload["unit_of_measurement"] = json!(sensor.sensor_type.unit());
+    }
+
+    if let Some(device_class) = device_class(sensor.sensor_type) {
+        payload["device_class"] = json!(device_class);
+    }
+
+    (topic, payload)
+}
+
+// the home assistant device class, only where it accepts the unit the sensor is reported in
+fn device_class(sensor_type: SensorType) -> Option<&'static str> {
+    match sensor_type {
+        SensorType::Voltage => Some("voltage"),
+        SensorType::Current => Some("current"),
+        SensorType::Power => Some("power"),
+        SensorType::Clock | SensorType::Frequency => Some("frequency"),
+        SensorType::Temperature => Some("temperature"),
+        SensorType::Data | SensorType::SmallData => Some("data_size"),
+        SensorType::Throughput => Some("data_rate"),
+        SensorType::TimeSpan => Some("duration"),
+        SensorType::Noise => Some("sound_pressure"),
+        // factors are ratios such as write amplification, not a power factor
+        SensorType::Factor
+        | SensorType::Load
+        | SensorType::Fan
+        | SensorType::Flow
+        | SensorType::Control
+        | SensorType::Level
+        | SensorType::Energy => None,
+    }
+}
+
+// home assistant ids only allow letters, digits, _ and -
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '-' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,297 @@
+use std::fmt::Display;
+use std::fs::{create_dir_all, File};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use chrono::Local;
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, FloatType, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::parser::parse_message_type;
+use tokio::sync::oneshot;
+
+use crate::system::Report;
+
+type Result<T> = std::result::Result<T, Error>;
+
+// created in the working directory, next to config.json when the app is started from its own folder
+const RECORDING_DIRECTORY: &str = "recordings";
+
+// rows are buffered in memory and written out as a row group once this many have accumulated
+const ROW_GROUP_SIZE: usize = 100_000;
+
+// one row per timestamp per sensor
+const SCHEMA: &str = "
+message recording {
+    REQUIRED INT64 timestamp (TIMESTAMP(MILLIS,true));
+    REQUIRED BYTE_ARRAY hardware (STRING);
+    REQUIRED BYTE_ARRAY hardware_type (STRING);
+    REQUIRED BYTE_ARRAY sensor (STRING);
+    REQUIRED BYTE_ARRAY sensor_type (STRING);
+    REQUIRED BYTE_ARRAY identifier (STRING);
+    REQUIRED FLOAT value;
+    REQUIRED FLOAT min;
+    REQUIRED FLOAT max;
+}
+";
+
+// error type
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io(io::Error),
+    Parquet(ParquetError),
+}
+
+// implement from for parquet error
+impl From<ParquetError> for Error {
+    fn from(error: ParquetError) -> Self {
+        Self::Parquet(error)
+    }
+}
+
+// implement from for io error
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// implement display for error
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Parquet(error) => write!(f, "Parquet error: {}", error),
+        }
+    }
+}
+
+// column buffers for the rows that have not been written yet
+#[derive(Default)]
+struct Columns {
+    timestamp: Vec<i64>,
+    hardware: Vec<ByteArray>,
+    hardware_type: Vec<ByteArray>,
+    sensor: Vec<ByteArray>,
+    sensor_type: Vec<ByteArray>,
+    identifier: Vec<ByteArray>,
+    value: Vec<f32>,
+    min: Vec<f32>,
+    max: Vec<f32>,
+}
+
+impl Columns {
+    fn len(&self) -> usize {
+        self.timestamp.len()
+    }
+}
+
+// what the recording thread is asked to do, answered over the oneshot
+enum Request {
+    Write(
+        Arc<Report>,
+        oneshot::Sender<std::result::Result<(), String>>,
+    ),
+    Finish(oneshot::Sender<std::result::Result<PathBuf, String>>),
+}
+
+// streams every report to a parquet file for the duration of a recording, the file is written
+// on its own thread in the order the reports were sent, so finishing waits for every write
+pub(crate) struct Recorder {
+    // the file being written
+    pub(crate) path: PathBuf,
+    requests: Sender<Request>,
+}
+
+impl Recorder {
+    // start a new recording in the recordings directory
+    pub(crate) fn create() -> Result<Self> {
+        let writer = Writer::create()?;
+        let path = writer.path.clone();
+        let (requests, receiver) = channel();
+
+        thread::spawn(move || run(writer, receiver));
+
+        Ok(Self { path, requests })
+    }
+
+    // queue a report, resolves once it has been written
+    pub(crate) fn write(
+        &self,
+        report: Arc<Report>,
+    ) -> impl Future<Output = std::result::Result<(), String>> {
+        let (done, result) = oneshot::channel();
+        let _ = self.requests.send(Request::Write(report, done));
+
+        async move {
+            result
+                .await
+                .unwrap_or_else(|_| Err(String::from("the recording has stopped")))
+        }
+    }
+
+    // finish after the queued reports, resolves with the path of the finished file
+    pub(crate) fn finish(self) -> impl Future<Output = std::result::Result<PathBuf, String>> {
+        let (done, result) = oneshot::channel();
+        let _ = self.requests.send(Request::Finish(done));
+
+        async move {
+            result
+                .await
+                .unwrap_or_else(|_| Err(String::from("the recording has stopped")))
+        }
+    }
+}
+
+// answer requests until the recording is finished or the recorder is dropped
+fn run(mut writer: Writer, requests: Receiver<Request>) {
+    for request in requests {
+        match request {
+            Request::Write(report, done) => {
+                let _ = done.send(writer.write(&report).map_err(|error| error.to_string()));
+            }
+            Request::Finish(done) => {
+                let _ = done.send(
+                    writer
+                        .finish()
+                        .map(|_| writer.path.clone())
+                        .map_err(|error| error.to_string()),
+                );
+                return;
+            }
+        }
+    }
+}
+
+// the parquet file and the rows not yet written to it
+struct Writer {
+    path: PathBuf,
+    writer: SerializedFileWriter<File>,
+    columns: Columns,
+}
+
+impl Writer {
+    fn create() -> Result<Self> {
+        let directory = Path::new(RECORDING_DIRECTORY);
+        create_dir_all(directory)?;
+
+        let path = directory.join(format!(
+            "recording-{}.parquet",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        let schema = Arc::new(parse_message_type(SCHEMA)?);
+        let properties = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+        );
+
+        let file = File::create(&path)?;
+        let writer = SerializedFileWriter::new(file, schema, properties)?;
+
+        Ok(Self {
+            path,
+            writer,
+            columns: Columns::default(),
+        })
+    }
+
+    // buffer the sensors in a report, writing a row group when the buffer is full
+    fn write(&mut self, report: &Report) -> Result<()> {
+        let timestamp = report.timestamp.timestamp_millis();
+
+        for hardware in &report.hardware {
+            let hardware_type = hardware.hardware_type.to_string();
+
+            for sensor in &hardware.sensors {
+                self.columns.timestamp.push(timestamp);
+                self.columns.hardware.push(hardware.name.as_str().into());
+                self.columns
+                    .hardware_type
+                    .push(hardware_type.as_str().into());
+                self.columns.sensor.push(sensor.name.as_str().into());
+                self.columns
+                    .sensor_type
+                    .push(sensor.sensor_type.to_string().as_str().into());
+                self.columns
+                    .identifier
+                    .push(sensor.identifier.as_str().into());
+                self.columns.value.push(sensor.value);
+                self.columns.min.push(sensor.min);
+                self.columns.max.push(sensor.max);
+            }
+        }
+
+        if self.columns.len() >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    // write any buffered rows and the file footer, the file is not readable until this is called
+    fn finish(&mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.finish()?;
+        Ok(())
+    }
+
+    // write the buffered rows as a single row group
+    fn flush(&mut self) -> Result<()> {
+        if self.columns.len() == 0 {
+            return Ok(());
+        }
+
+        let columns = std::mem::take(&mut self.columns);
+        let mut row_group = self.writer.next_row_group()?;
+        let mut index = 0;
+
+        // columns are handed out in schema order
+        while let Some(mut column) = row_group.next_column()? {
+            match index {
+                0 => {
+                    column
+                        .typed::<Int64Type>()
+                        .write_batch(&columns.timestamp, None, None)?;
+                }
+                1 => write_strings(&mut column, &columns.hardware)?,
+                2 => write_strings(&mut column, &columns.hardware_type)?,
+                3 => write_strings(&mut column, &columns.sensor)?,
+                4 => write_strings(&mut column, &columns.sensor_type)?,
+                5 => write_strings(&mut column, &columns.identifier)?,
+                6 => write_floats(&mut column, &columns.value)?,
+                7 => write_floats(&mut column, &columns.min)?,
+                _ => write_floats(&mut column, &columns.max)?,
+            }
+
+            column.close()?;
+            index += 1;
+        }
+
+        row_group.close()?;
+        Ok(())
+    }
+}
+
+// write a batch of values to a string column
+fn write_strings(column: &mut SerializedColumnWriter, values: &[ByteArray]) -> Result<()> {
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(values, None, None)?;
+    Ok(())
+}
+
+// write a batch of values to a float column
+fn write_floats(column: &mut SerializedColumnWriter, values: &[f32]) -> Result<()> {
+    column
+        .typed::<FloatType>()
+        .write_batch(values, None, None)?;
+    Ok(())
+}
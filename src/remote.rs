@@ -0,0 +1,230 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message as Frame;
+use tokio_tungstenite::{client_async, WebSocketStream};
+
+use crate::system::{Hardware, HardwareType, Report, Sensor, SensorType};
+
+// how long to wait before reconnecting after the connection is lost
+const RECONNECT_SECONDS: u64 = 5;
+
+// how long connecting may take
+const CONNECT_TIMEOUT_SECONDS: u64 = 10;
+
+// the other instance sends a report every second, this long without one means the connection is gone
+const STALE_SECONDS: u64 = 30;
+
+// another corroded monitor to show instead of this machine, read from its websocket stream
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RemoteConfig {
+    pub(crate) enabled: bool,
+    // the address its server listens on, e.g. 192.168.1.20:9184
+    pub(crate) address: String,
+    // the token set in its config, if any
+    pub(crate) token: Option<String>,
+}
+
+// what happened to the connection
+#[derive(Debug, Clone)]
+pub(crate) enum Event {
+    Connected,
+    Report(Arc<Report>),
+    Disconnected(String),
+}
+
+// a frame of the stream, see server::stream
+#[derive(Debug, Deserialize)]
+struct Readings {
+    timestamp: String,
+    hardware: Vec<RemoteHardware>,
+    sensors: Vec<RemoteSensor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteHardware {
+    identifier: String,
+    name: String,
+    #[serde(rename = "type")]
+    hardware_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteSensor {
+    identifier: String,
+    hardware: String,
+    name: String,
+    #[serde(rename = "type")]
+    sensor_type: String,
+    index: usize,
+    value: f32,
+    min: f32,
+    max: f32,
+}
+
+// stay connected to the other instance, reconnecting whenever the connection is lost
+pub(crate) fn connect(config: RemoteConfig) -> Subscription<Event> {
+    // a new address or token starts a new connection
+    let id = (config.address.clone(), config.token.clone());
+
+    subscription::channel(id, 16, |mut output| async move {
+        loop {
+            let reason = match stream(&config, &mut output).await {
+                Ok(()) => String::from("the connection was closed"),
+                Err(error) => error,
+            };
+
+            let _ = output.send(Event::Disconnected(reason)).await;
+            sleep(Duration::from_secs(RECONNECT_SECONDS)).await;
+        }
+    })
+}
+
+// receive reports until the connection fails
+async fn stream(config: &RemoteConfig, output: &mut Sender<Event>) -> Result<(), String> {
+    let mut socket = open(&config.address, config.token.as_deref()).await?;
+
+    output
+        .send(Event::Connected)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    loop {
+        let frame = timeout(Duration::from_secs(STALE_SECONDS), socket.next())
+            .await
+            .map_err(|_| format!("no readings for {} seconds", STALE_SECONDS))?;
+
+        match frame {
+            Some(Ok(Frame::Text(text))) => {
+                let report = parse(&text)?;
+
+                output
+                    .send(Event::Report(Arc::new(report)))
+                    .await
+                    .map_err(|error| error.to_string())?;
+            }
+            Some(Ok(Frame::Close(_))) | None => return Ok(()),
+            Some(Err(error)) => return Err(error.to_string()),
+            // pings are answered by tungstenite
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+// read a single report, the server sends the latest one as soon as the connection is open
+pub(crate) async fn fetch(address: String, token: Option<String>) -> Result<Report, String> {
+    let mut socket = open(&address, token.as_deref()).await?;
+
+    let report = loop {
+        let frame = timeout(Duration::from_secs(CONNECT_TIMEOUT_SECONDS), socket.next())
+            .await
+            .map_err(|_| String::from("timed out waiting for readings"))?;
+
+        match frame {
+            Some(Ok(Frame::Text(text))) => break parse(&text)?,
+            Some(Ok(Frame::Close(_))) | None => {
+                return Err(String::from("the connection was closed"))
+            }
+            Some(Err(error)) => return Err(error.to_string()),
+            Some(Ok(_)) => {}
+        }
+    };
+
+    let _ = socket.close(None).await;
+
+    Ok(report)
+}
+
+// percent encode everything but unreserved characters, so a token with & or # stays one query value
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+// connect to the other instance's stream
+async fn open(address: &str, token: Option<&str>) -> Result<WebSocketStream<TcpStream>, String> {
+    let mut url = format!("ws://{}/stream", address);
+
+    // browsers cannot set headers either, so the server accepts the token in the query
+    if let Some(token) = token {
+        url.push_str(&format!("?token={}", encode(token)));
+    }
+
+    let connection = timeout(
+        Duration::from_secs(CONNECT_TIMEOUT_SECONDS),
+        TcpStream::connect(address),
+    )
+    .await
+    .map_err(|_| String::from("timed out connecting"))?
+    .map_err(|error| error.to_string())?;
+
+    let (socket, _) = client_async(url, connection)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    Ok(socket)
+}
+
+// rebuild the report the other instance read, sensors are sent in the order of their hardware
+fn parse(text: &str) -> Result<Report, String> {
+    let readings: Readings = serde_json::from_str(text)
+        .map_err(|_| format!("unexpected message from the server: {}", text))?;
+
+    let timestamp = DateTime::parse_from_rfc3339(&readings.timestamp)
+        .map_err(|error| error.to_string())?
+        .with_timezone(&Local);
+
+    let mut hardware: Vec<Hardware> = readings
+        .hardware
+        .into_iter()
+        .filter_map(|item| {
+            Some(Hardware {
+                // types from a newer version are skipped
+                hardware_type: HardwareType::from_name(&item.hardware_type)?,
+                identifier: item.identifier,
+                name: item.name,
+                sensors: Vec::new(),
+            })
+        })
+        .collect();
+
+    for item in readings.sensors {
+        let Some(sensor_type) = SensorType::from_name(&item.sensor_type) else {
+            continue;
+        };
+
+        if let Some(parent) = hardware
+            .iter_mut()
+            .find(|parent| parent.identifier == item.hardware)
+        {
+            parent.sensors.push(Sensor {
+                sensor_type,
+                identifier: item.identifier,
+                name: item.name,
+                index: item.index,
+                value: item.value,
+                max: item.max,
+                min: item.min,
+            });
+        }
+    }
+
+    Ok(Report {
+        timestamp,
+        hardware,
+    })
+}
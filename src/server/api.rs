@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, TimeZone};
+use serde_json::{json, Value};
+
+use crate::server::{Histories, Reports, Request, Response};
+use crate::system::history::History;
+use crate::system::{Hardware, Report, Sensor};
+
+// how far back history goes when no since is given
+const DEFAULT_HISTORY_SECONDS: i64 = 600;
+
+// answer a request for the json api, none if the path is not part of it
+pub(super) async fn route(
+    request: &Request,
+    reports: &Reports,
+    history: &Histories,
+) -> Option<Response> {
+    let path = request.path.as_str();
+
+    // sensor identifiers contain slashes, e.g. /sensors/amdcpu/0/temperature/2/history
+    if let Some(identifier) = path
+        .strip_prefix("/sensors/")
+        .and_then(|path| path.strip_suffix("/history"))
+    {
+        let since = match request.query("since") {
+            Some(since) => match parse_since(since) {
+                Some(since) => since,
+                None => {
+                    return Some(error(
+                        400,
+                        "since must be an RFC 3339 timestamp or unix seconds",
+                    ))
+                }
+            },
+            None => Local::now() - Duration::seconds(DEFAULT_HISTORY_SECONDS),
+        };
+
+        let Some(history) = latest(history.clone()).await else {
+            return Some(error(503, "no history yet"));
+        };
+
+        return Some(
+            match sensor_history(&history, &identifier_from(identifier), since) {
+                Some(value) => Response::json(200, &value),
+                None => error(404, "no history for this sensor"),
+            },
+        );
+    }
+
+    if !["/hardware", "/sensors"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return None;
+    }
+
+    // everything else is read from the latest report
+    let Some(report) = reports.borrow().clone() else {
+        return Some(error(503, "no readings yet"));
+    };
+
+    let value = if path == "/hardware" {
+        Some(json!({
+            "timestamp": report.timestamp.to_rfc3339(),
+            "hardware": report.hardware.iter().map(hardware).collect::<Vec<_>>(),
+        }))
+    } else if path == "/sensors" {
+        Some(json!({
+            "timestamp": report.timestamp.to_rfc3339(),
+            "sensors": report
+                .hardware
+                .iter()
+                .flat_map(|hardware| {
+                    hardware.sensors.iter().map(move |item| sensor(hardware, item))
+                })
+                .collect::<Vec<_>>(),
+        }))
+    } else if let Some(identifier) = path
+        .strip_prefix("/hardware/")
+        .and_then(|path| path.strip_suffix("/sensors"))
+    {
+        hardware_sensors(&report, &identifier_from(identifier))
+    } else if let Some(identifier) = path.strip_prefix("/sensors/") {
+        single_sensor(&report, &identifier_from(identifier))
+    } else {
+        None
+    };
+
+    Some(match value {
+        Some(value) => Response::json(200, &value),
+        None => error(404, "no hardware or sensor with this identifier"),
+    })
+}
+
+// identifiers start with a slash, which may also have been sent encoded as %2F
+fn identifier_from(path: &str) -> String {
+    format!("/{}", path.trim_start_matches('/'))
+}
+
+fn error(status: u16, message: &str) -> Response {
+    Response::json(status, &json!({ "error": message }))
+}
+
+pub(super) fn hardware(hardware: &Hardware) -> Value {
+    json!({
+        "identifier": hardware.identifier,
+        "name": hardware.name,
+        "type": hardware.hardware_type.to_string(),
+        "sensors": hardware.sensors.len(),
+    })
+}
+
+pub(super) fn sensor(hardware: &Hardware, sensor: &Sensor) -> Value {
+    json!({
+        "identifier": sensor.identifier,
+        "hardware": hardware.identifier,
+        "name": sensor.name,
+        "type": sensor.sensor_type.to_string(),
+        "index": sensor.index,
+        "unit": sensor.sensor_type.unit(),
+        "value": sensor.value,
+        "min": sensor.min,
+        "max": sensor.max,
+    })
+}
+
+// GET /hardware/{id}/sensors
+fn hardware_sensors(report: &Report, identifier: &str) -> Option<Value> {
+    let found = report
+        .hardware
+        .iter()
+        .find(|hardware| hardware.identifier == identifier)?;
+
+    Some(json!({
+        "timestamp": report.timestamp.to_rfc3339(),
+        "hardware": hardware(found),
+        "sensors": found.sensors.iter().map(|item| sensor(found, item)).collect::<Vec<_>>(),
+    }))
+}
+
+// GET /sensors/{id}
+fn single_sensor(report: &Report, identifier: &str) -> Option<Value> {
+    report.hardware.iter().find_map(|hardware| {
+        hardware
+            .sensors
+            .iter()
+            .find(|sensor| sensor.identifier == identifier)
+            .map(|found| {
+                json!({
+                    "timestamp": report.timestamp.to_rfc3339(),
+                    "sensor": sensor(hardware, found),
+                })
+            })
+    })
+}
+
+// the app only takes the history away while adding to it, so wait for it to be put back
+async fn latest(mut histories: Histories) -> Option<Arc<History>> {
+    loop {
+        if let Some(history) = histories.borrow_and_update().clone() {
+            return Some(history);
+        }
+
+        // the app has closed
+        histories.changed().await.ok()?;
+    }
+}
+
+// GET /sensors/{id}/history, bucketed like the graphs so long ranges stay small
+fn sensor_history(history: &History, identifier: &str, since: DateTime<Local>) -> Option<Value> {
+    let sensor = history.get(identifier)?;
+    let (resolution, buckets) = sensor.graph.since(since);
+
+    Some(json!({
+        "identifier": identifier,
+        "hardware": sensor.hardware,
+        "hardware_name": sensor.hardware_name,
+        "name": sensor.name,
+        "type": sensor.sensor_type.to_string(),
+        "unit": sensor.sensor_type.unit(),
+        "resolution_seconds": resolution,
+        "samples": buckets
+            .into_iter()
+            .map(|(start, average, minimum, maximum)| json!({
+                "timestamp": start.to_rfc3339(),
+                "value": average,
+                "min": minimum,
+                "max": maximum,
+            }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+// an rfc 3339 timestamp, e.g. 2024-01-01T12:00:00Z, or unix seconds
+fn parse_since(value: &str) -> Option<DateTime<Local>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Local));
+    }
+
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| Local.timestamp_opt(seconds, 0).single())
+}
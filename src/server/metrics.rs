@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::system::{Report, SensorType};
+
+// the prometheus text exposition format
+pub(crate) const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// the metric a sensor type is exposed as, named after its base unit
+fn metric(sensor_type: SensorType) -> (&'static str, &'static str) {
+    match sensor_type {
+        SensorType::Voltage => ("corroded_voltage_volts", "Voltage in volts"),
+        SensorType::Current => ("corroded_current_amperes", "Current in amperes"),
+        SensorType::Power => ("corroded_power_watts", "Power draw in watts"),
+        SensorType::Clock => ("corroded_clock_megahertz", "Clock speed in megahertz"),
+        SensorType::Temperature => (
+            "corroded_temperature_celsius",
+            "Temperature in degrees celsius",
+        ),
+        SensorType::Load => ("corroded_load_percent", "Load in percent"),
+        SensorType::Frequency => ("corroded_frequency_hertz", "Frequency in hertz"),
+        SensorType::Fan => ("corroded_fan_rpm", "Fan speed in revolutions per minute"),
+        SensorType::Flow => (
+            "corroded_flow_liters_per_hour",
+            "Flow rate in liters per hour",
+        ),
+        SensorType::Control => ("corroded_control_percent", "Fan control in percent"),
+        SensorType::Level => ("corroded_level_percent", "Level in percent"),
+        SensorType::Factor => ("corroded_factor_ratio", "Unitless factor"),
+        SensorType::Data => ("corroded_data_gigabytes", "Data in gigabytes"),
+        SensorType::SmallData => ("corroded_small_data_megabytes", "Data in megabytes"),
+        SensorType::Throughput => (
+            "corroded_throughput_bytes_per_second",
+            "Throughput in bytes per second",
+        ),
+        SensorType::TimeSpan => ("corroded_time_span_seconds", "Time span in seconds"),
+        SensorType::Energy => (
+            "corroded_energy_milliwatt_hours",
+            "Energy in milliwatt hours",
+        ),
+        SensorType::Noise => ("corroded_noise_decibels", "Noise in A-weighted decibels"),
+    }
+}
+
+// every sensor in the report, grouped by metric since a metric's samples have to be together
+pub(crate) fn render(report: &Report) -> String {
+    let mut metrics: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+
+    for hardware in &report.hardware {
+        for sensor in &hardware.sensors {
+            // the identifier keeps identical hardware, e.g. two of the same disk, apart
+            let labels = format!(
+                "hardware_type=\"{}\",hardware=\"{}\",sensor=\"{}\",index=\"{}\",identifier=\"{}\"",
+                hardware.hardware_type,
+                escape(&hardware.name),
+                escape(&sensor.name),
+                sensor.index,
+                escape(&sensor.identifier)
+            );
+
+            metrics
+                .entry(metric(sensor.sensor_type))
+                .or_default()
+                .push(format!("{{{}}} {}", labels, sensor.value));
+        }
+    }
+
+    let mut output = String::new();
+
+    for ((name, help), samples) in metrics {
+        let _ = writeln!(output, "# HELP {} {}", name, help);
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+
+        for sample in samples {
+            let _ = writeln!(output, "{}{}", name, sample);
+        }
+    }
+
+    let _ = writeln!(
+        output,
+        "# HELP corroded_last_update_timestamp_seconds When the sensors were last read"
+    );
+    let _ = writeln!(
+        output,
+        "# TYPE corroded_last_update_timestamp_seconds gauge"
+    );
+    let _ = writeln!(
+        output,
+        "corroded_last_update_timestamp_seconds {:.3}",
+        report.timestamp.timestamp_millis() as f64 / 1000_f64
+    );
+
+    output
+}
+
+// label values are quoted, so backslashes, quotes and newlines are escaped
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+    use crate::system::{Hardware, HardwareType, Sensor};
+
+    // a sensor which has only ever read one value
+    fn sensor(sensor_type: SensorType, identifier: &str, name: &str, value: f32) -> Sensor {
+        Sensor {
+            sensor_type,
+            identifier: String::from(identifier),
+            name: String::from(name),
+            index: 1,
+            value,
+            max: value,
+            min: value,
+        }
+    }
+
+    // names with spaces, commas, equals signs, quotes, backslashes and line breaks
+    #[test]
+    fn exposition() {
+        let report = Report {
+            timestamp: Local.timestamp_opt(1_700_000_000, 250_000_000).unwrap(),
+            hardware: vec![Hardware {
+                hardware_type: HardwareType::Cpu,
+                identifier: String::from("/amdcpu/0"),
+                name: String::from("Ryzen 7, \"X3D\""),
+                sensors: vec![
+                    sensor(
+                        SensorType::Temperature,
+                        "/amdcpu/0/temperature/2",
+                        "Core #1 a=b",
+                        54.5,
+                    ),
+                    sensor(SensorType::Load, "/amdcpu/0/load/0", "CPU Total", 12_f32),
+                    sensor(
+                        SensorType::Temperature,
+                        "/amdcpu/0/temperature/3",
+                        "C:\\temp\nnext",
+                        -3_f32,
+                    ),
+                ],
+            }],
+        };
+
+        assert_eq!(
+            render(&report),
+            concat!(
+                "# HELP corroded_load_percent Load in percent\n",
+                "# TYPE corroded_load_percent gauge\n",
+                "corroded_load_percent{hardware_type=\"Cpu\",hardware=\"Ryzen 7, \\\"X3D\\\"\",sensor=\"CPU Total\",index=\"1\",identifier=\"/amdcpu/0/load/0\"} 12\n",
+                "# HELP corroded_temperature_celsius Temperature in degrees celsius\n",
+                "# TYPE corroded_temperature_celsius gauge\n",
+                "corroded_temperature_celsius{hardware_type=\"Cpu\",hardware=\"Ryzen 7, \\\"X3D\\\"\",sensor=\"Core #1 a=b\",index=\"1\",identifier=\"/amdcpu/0/temperature/2\"} 54.5\n",
+                "corroded_temperature_celsius{hardware_type=\"Cpu\",hardware=\"Ryzen 7, \\\"X3D\\\"\",sensor=\"C:\\\\temp\\nnext\",index=\"1\",identifier=\"/amdcpu/0/temperature/3\"} -3\n",
+                "# HELP corroded_last_update_timestamp_seconds When the sensors were last read\n",
+                "# TYPE corroded_last_update_timestamp_seconds gauge\n",
+                "corroded_last_update_timestamp_seconds 1700000000.250\n",
+            )
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("plain name"), "plain name");
+        assert_eq!(escape("a \"b\", c=d"), "a \\\"b\\\", c=d");
+        assert_eq!(escape("back\\slash\nline"), "back\\\\slash\\nline");
+    }
+}
@@ -0,0 +1,428 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::{sleep, timeout};
+
+use crate::system::history::History;
+use crate::system::Report;
+
+mod api;
+mod metrics;
+mod stream;
+
+// loopback, so the server is only reachable from this machine unless the address is changed
+pub(crate) const DEFAULT_ADDRESS: &str = "127.0.0.1:9184";
+
+// requests with a larger head are dropped
+const MAXIMUM_HEAD_BYTES: usize = 16 * 1024;
+
+// how long a client has to send its request
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+// the web dashboard, a single page fed by the websocket stream
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+// how many times binding is tried, the previous server may still be letting go of the address
+const BIND_ATTEMPTS: u32 = 10;
+
+// the latest report, shared with every connection
+pub(crate) type Reports = watch::Receiver<Option<Arc<Report>>>;
+
+// the history kept by the app, none while it is being changed
+pub(crate) type Histories = watch::Receiver<Option<Arc<History>>>;
+
+// what the server listens on and which endpoints it serves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ServerConfig {
+    pub(crate) address: String,
+    // the sensors in the prometheus text format at /metrics
+    pub(crate) metrics: bool,
+    // the json api at /hardware and /sensors
+    pub(crate) api: bool,
+    // every new reading pushed over a websocket at /stream
+    pub(crate) stream: bool,
+    // a web page at / showing the readings like the app does, it reads the stream
+    pub(crate) dashboard: bool,
+    // when set, every request has to send it in an Authorization: Bearer header
+    pub(crate) token: Option<String>,
+    // pages on other sites allowed to open the stream, e.g. http://grafana.local:3000, the dashboard always is
+    pub(crate) allowed_origins: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            address: String::from(DEFAULT_ADDRESS),
+            metrics: false,
+            api: false,
+            stream: false,
+            dashboard: false,
+            token: None,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    // the server only runs when it has something to serve
+    pub(crate) fn enabled(&self) -> bool {
+        self.metrics || self.api || self.streams()
+    }
+
+    // the dashboard needs the stream, so it is served for either
+    fn streams(&self) -> bool {
+        self.stream || self.dashboard
+    }
+}
+
+// a running server, stopped when dropped
+#[derive(Debug)]
+pub(crate) struct Server {
+    pub(crate) address: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+// what every connection reads from
+#[derive(Clone)]
+struct Shared {
+    config: Arc<ServerConfig>,
+    reports: Reports,
+    // kept by the app so it outlives restarts
+    history: Histories,
+}
+
+// asynchronously start listening, each connection is handled in its own task
+pub(crate) async fn start(
+    config: ServerConfig,
+    reports: Reports,
+    history: Histories,
+) -> Result<Arc<Server>, String> {
+    let listener = bind(&config.address)
+        .await
+        .map_err(|error| format!("Failed to listen on {}: {}", config.address, error))?;
+    let address = listener.local_addr().map_err(|error| error.to_string())?;
+
+    let shared = Shared {
+        config: Arc::new(config),
+        reports,
+        history,
+    };
+
+    let tasks = vec![tokio::spawn(async move {
+        // owned by the accept loop, so stopping the server also closes open websockets
+        let mut connections = JoinSet::new();
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        connections.spawn(handle(stream, shared.clone()));
+                    }
+                    // e.g. out of file handles, give the open connections a moment to finish
+                    Err(_) => sleep(Duration::from_millis(100)).await,
+                },
+                // forget finished connections
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    })];
+
+    Ok(Arc::new(Server { address, tasks }))
+}
+
+async fn bind(address: &str) -> io::Result<TcpListener> {
+    let mut attempt = 1;
+
+    loop {
+        match TcpListener::bind(address).await {
+            Err(error) if error.kind() == io::ErrorKind::AddrInUse && attempt < BIND_ATTEMPTS => {
+                attempt += 1;
+                sleep(Duration::from_millis(100)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+// a parsed request line and headers, the body is not read
+#[derive(Debug)]
+struct Request {
+    method: String,
+    // percent decoded
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // header names are compared ignoring case
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // whether the request carries the token, if one is required, browsers cannot set headers on
+    // websockets so it can also be sent as ?token=
+    fn authorized(&self, token: Option<&str>) -> bool {
+        match token {
+            Some(token) => self
+                .header("Authorization")
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .or_else(|| self.query("token"))
+                .is_some_and(|value| constant_time_eq(value.trim(), token)),
+            None => true,
+        }
+    }
+}
+
+// compares every byte so the time taken does not tell how much of the token was right
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    fn text(status: u16, body: &str) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body)
+    }
+
+    fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self::new(status, "application/json", body.to_string())
+    }
+
+    async fn write(&self, stream: &mut TcpStream) -> io::Result<()> {
+        // tells clients which scheme to use when a token is missing
+        let challenge = if self.status == 401 {
+            "WWW-Authenticate: Bearer\r\n"
+        } else {
+            ""
+        };
+
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len(),
+            challenge
+        );
+
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&self.body).await?;
+        stream.flush().await
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        426 => "Upgrade Required",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+// one request per connection, the connection is closed after the response
+async fn handle(mut stream: TcpStream, shared: Shared) {
+    let request = match timeout(
+        Duration::from_secs(REQUEST_TIMEOUT_SECONDS),
+        read_request(&mut stream),
+    )
+    .await
+    {
+        Ok(Ok(Some(request))) => request,
+        Ok(Ok(None)) => {
+            let _ = Response::text(400, "Bad request").write(&mut stream).await;
+            return;
+        }
+        // timed out or the connection failed
+        _ => return,
+    };
+
+    // the websocket keeps the connection, everything else gets one response
+    if request.path == "/stream"
+        && shared.config.streams()
+        && request.method == "GET"
+        && request.authorized(shared.config.token.as_deref())
+        && stream::is_upgrade(&request)
+    {
+        // browsers let any page open a websocket, so pages from other sites are turned away
+        if !stream::allowed_origin(&request, &shared.config.allowed_origins) {
+            let _ = Response::text(403, "Origin not allowed")
+                .write(&mut stream)
+                .await;
+            return;
+        }
+
+        stream::serve(stream, request, shared.reports).await;
+        return;
+    }
+
+    let response = route(&request, &shared).await;
+    let _ = response.write(&mut stream).await;
+}
+
+async fn route(request: &Request, shared: &Shared) -> Response {
+    if request.method != "GET" {
+        return Response::text(405, "Method not allowed");
+    }
+
+    if !request.authorized(shared.config.token.as_deref()) {
+        return Response::text(401, "Unauthorized");
+    }
+
+    if request.path == "/metrics" && shared.config.metrics {
+        return match shared.reports.borrow().as_ref() {
+            Some(report) => Response::new(200, metrics::CONTENT_TYPE, metrics::render(report)),
+            None => Response::text(503, "No readings yet"),
+        };
+    }
+
+    if request.path == "/" && shared.config.dashboard {
+        return Response::new(200, "text/html; charset=utf-8", DASHBOARD);
+    }
+
+    // the websocket upgrade was handled before routing, so this is a plain request
+    if request.path == "/stream" && shared.config.streams() {
+        return Response::text(426, "Expected a websocket upgrade");
+    }
+
+    if shared.config.api {
+        if let Some(response) = api::route(request, &shared.reports, &shared.history).await {
+            return response;
+        }
+    }
+
+    Response::text(404, "Not found")
+}
+
+// read up to the end of the headers, returns none if the request is malformed
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 1024];
+
+    let end = loop {
+        if let Some(index) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index;
+        }
+
+        if buffer.len() > MAXIMUM_HEAD_BYTES {
+            return Ok(None);
+        }
+
+        let read = stream.read(&mut chunk).await?;
+
+        if read == 0 {
+            return Ok(None);
+        }
+
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..end]);
+    let mut lines = head.split("\r\n");
+
+    // e.g. GET /sensors/amdcpu/0/load/0/history?since=1700000000 HTTP/1.1
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: decode(path),
+        query,
+        headers,
+    }))
+}
+
+// undo percent encoding, e.g. %2F to /, invalid escapes are kept as they are
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,157 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message as Frame;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::alert::wildcard;
+use crate::server::api::{hardware, sensor};
+use crate::server::{Reports, Request, Response};
+use crate::system::Report;
+
+// sent by clients to change which sensors they receive, e.g. {"subscribe": ["/amdcpu/0/*"]}
+#[derive(Debug, Deserialize)]
+struct Subscription {
+    subscribe: Vec<String>,
+}
+
+// whether the request asks to switch to a websocket
+pub(super) fn is_upgrade(request: &Request) -> bool {
+    request
+        .header("Upgrade")
+        .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
+// whether the upgrade comes from the dashboard, an allowed page, or not from a browser at all
+pub(super) fn allowed_origin(request: &Request, allowed: &[String]) -> bool {
+    let Some(origin) = request.header("Origin") else {
+        // only browsers send an origin, other clients such as the remote mode of the app do not
+        return true;
+    };
+
+    let same_host = origin
+        .split_once("://")
+        .zip(request.header("Host"))
+        .is_some_and(|((_, host), request_host)| host.eq_ignore_ascii_case(request_host));
+
+    same_host
+        || allowed
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+}
+
+// finish the handshake and push every new report to the client until it disconnects
+pub(super) async fn serve(mut stream: TcpStream, request: Request, mut reports: Reports) {
+    let Some(key) = request.header("Sec-WebSocket-Key") else {
+        let _ = Response::text(400, "Missing Sec-WebSocket-Key")
+            .write(&mut stream)
+            .await;
+        return;
+    };
+
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+
+    if stream.write_all(head.as_bytes()).await.is_err() {
+        return;
+    }
+
+    let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+
+    // sensor identifiers or globs, every sensor when empty
+    let mut patterns: Vec<String> = request
+        .query("sensors")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(String::from)
+        .collect();
+
+    // start with the latest report so clients do not wait for the next update
+    let latest = reports.borrow_and_update().clone();
+
+    if let Some(report) = latest {
+        if socket.send(frame(&report, &patterns)).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            changed = reports.changed() => {
+                // the app is closing
+                if changed.is_err() {
+                    break;
+                }
+
+                let latest = reports.borrow_and_update().clone();
+
+                if let Some(report) = latest {
+                    if socket.send(frame(&report, &patterns)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            incoming = socket.next() => match incoming {
+                Some(Ok(Frame::Text(text))) => match serde_json::from_str::<Subscription>(&text) {
+                    Ok(subscription) => patterns = subscription.subscribe,
+                    Err(error) => {
+                        let reply = json!({
+                            "error": format!("expected {{\"subscribe\": [...]}}, {}", error),
+                        });
+
+                        if socket.send(Frame::Text(reply.to_string())).await.is_err() {
+                            break;
+                        }
+                    }
+                },
+                // pings are answered by tungstenite, anything else is ignored
+                Some(Ok(Frame::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.close(None).await;
+}
+
+// the sensors of the report matching the patterns, and the hardware they belong to
+fn frame(report: &Report, patterns: &[String]) -> Frame {
+    let mut components = Vec::new();
+    let mut sensors = Vec::new();
+
+    for item in &report.hardware {
+        let matching = item
+            .sensors
+            .iter()
+            .filter(|item| {
+                patterns.is_empty()
+                    || patterns
+                        .iter()
+                        .any(|pattern| wildcard(pattern, &item.identifier))
+            })
+            .map(|matching| sensor(item, matching))
+            .collect::<Vec<_>>();
+
+        if !matching.is_empty() {
+            components.push(hardware(item));
+            sensors.extend(matching);
+        }
+    }
+
+    Frame::Text(
+        json!({
+            "timestamp": report.timestamp.to_rfc3339(),
+            "hardware": components,
+            "sensors": sensors,
+        })
+        .to_string(),
+    )
+}
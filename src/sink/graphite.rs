@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::sink::{measurement, text, Error};
+use crate::system::Report;
+
+// where and how often the sensors are written in the graphite plaintext protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct GraphiteConfig {
+    pub(crate) enabled: bool,
+    // carbon's plaintext listener
+    pub(crate) address: String,
+    // the first part of every metric path
+    pub(crate) prefix: String,
+    pub(crate) interval_seconds: u64,
+}
+
+impl Default for GraphiteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: String::from("localhost:2003"),
+            prefix: String::from("corroded_monitor"),
+            interval_seconds: 10,
+        }
+    }
+}
+
+// a line per sensor, e.g. corroded_monitor.pc.cpu.amd_ryzen_7_5800x.temperature.cpu_package 54.5 1700000000
+pub(super) fn render(report: &Report, host: &str, prefix: &str) -> Vec<String> {
+    let timestamp = report.timestamp.timestamp();
+    let mut lines = Vec::new();
+
+    for hardware in &report.hardware {
+        for sensor in &hardware.sensors {
+            let path = [
+                prefix.to_string(),
+                segment(host),
+                segment(&hardware.hardware_type.to_string()),
+                segment(&hardware.name),
+                measurement(sensor.sensor_type).to_string(),
+                segment(&sensor.name),
+            ]
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join(".");
+
+            lines.push(format!("{} {} {}", path, sensor.value, timestamp));
+        }
+    }
+
+    lines
+}
+
+// a new connection per batch, carbon closes idle ones
+pub(super) async fn write(config: &GraphiteConfig, lines: &[Vec<u8>]) -> Result<(), Error> {
+    let mut stream = TcpStream::connect(&config.address).await?;
+    stream.write_all(&text(lines)).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+// dots separate the path, so names keep only lowercase letters, digits and underscores
+pub(super) fn segment(name: &str) -> String {
+    let mut segment = String::with_capacity(name.len());
+
+    for character in name.chars() {
+        if character.is_ascii_alphanumeric() {
+            segment.push(character.to_ascii_lowercase());
+        } else if !segment.ends_with('_') {
+            segment.push('_');
+        }
+    }
+
+    segment.trim_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::tests::report;
+
+    #[test]
+    fn plaintext() {
+        assert_eq!(
+            render(&report(), "my pc", "corroded_monitor"),
+            [
+                "corroded_monitor.my_pc.cpu.amd_ryzen_7_x3d_1.temperature.core_1_a_b_hot 54.5 1700000000",
+                "corroded_monitor.my_pc.cpu.amd_ryzen_7_x3d_1.load.cpu_total 12 1700000000",
+            ]
+        );
+
+        // empty segments are left out instead of leaving two dots
+        assert_eq!(
+            render(&report(), "", "")[1],
+            "cpu.amd_ryzen_7_x3d_1.load.cpu_total 12 1700000000"
+        );
+    }
+
+    #[test]
+    fn segments() {
+        assert_eq!(segment("AMD Ryzen 7, \"X3D\"=1"), "amd_ryzen_7_x3d_1");
+        assert_eq!(segment("  a..b__c  "), "a_b_c");
+        assert_eq!(segment("#!?"), "");
+    }
+}
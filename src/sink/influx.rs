@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+
+use crate::sink::{datagrams, measurement, post, text, Error};
+use crate::system::Report;
+
+// how the lines reach influxdb
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Transport {
+    // posted to the write endpoint of the url
+    Http,
+    // sent to the udp listener at the address
+    Udp,
+    // appended to the file at the path, e.g. for telegraf's tail input
+    File,
+}
+
+// where and how often the sensors are written in the influxdb line protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct InfluxConfig {
+    pub(crate) enabled: bool,
+    pub(crate) transport: Transport,
+    // the 1.x write endpoint, or /api/v2/write?org=...&bucket=... for 2.x
+    pub(crate) url: String,
+    // sent as Authorization: Token ..., needed by 2.x
+    pub(crate) token: Option<String>,
+    pub(crate) address: String,
+    pub(crate) path: String,
+    pub(crate) interval_seconds: u64,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            transport: Transport::Http,
+            url: String::from("http://localhost:8086/write?db=corroded_monitor"),
+            token: None,
+            address: String::from("localhost:8089"),
+            path: String::from("corroded_monitor.influx"),
+            interval_seconds: 10,
+        }
+    }
+}
+
+impl InfluxConfig {
+    pub(crate) fn destination(&self) -> String {
+        match self.transport {
+            Transport::Http => self.url.clone(),
+            Transport::Udp => format!("udp://{}", self.address),
+            Transport::File => self.path.clone(),
+        }
+    }
+}
+
+// a line per sensor, e.g. temperature,host=PC,hardware_type=Cpu,hardware=AMD\ Ryzen\ 7\ 5800X,... value=54.5 1700000000000000000
+pub(super) fn render(report: &Report, host: &str) -> Vec<String> {
+    let timestamp = report.timestamp.timestamp_nanos_opt().unwrap_or_default();
+    let mut lines = Vec::new();
+
+    for hardware in &report.hardware {
+        for sensor in &hardware.sensors {
+            let tags = [
+                ("host", host.to_string()),
+                ("hardware_type", hardware.hardware_type.to_string()),
+                ("hardware", hardware.name.clone()),
+                ("sensor", sensor.name.clone()),
+                ("identifier", sensor.identifier.clone()),
+            ];
+
+            // empty tag values are not allowed
+            let tags = tags
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| format!(",{}={}", key, escape(value)))
+                .collect::<String>();
+
+            lines.push(format!(
+                "{}{} value={} {}",
+                measurement(sensor.sensor_type),
+                tags,
+                sensor.value,
+                timestamp
+            ));
+        }
+    }
+
+    lines
+}
+
+pub(super) async fn write(config: &InfluxConfig, lines: &[Vec<u8>]) -> Result<(), Error> {
+    match config.transport {
+        Transport::Http => {
+            let headers = config
+                .token
+                .iter()
+                .map(|token| (String::from("Authorization"), format!("Token {}", token)))
+                .collect::<Vec<_>>();
+
+            post(
+                &config.url,
+                "text/plain; charset=utf-8",
+                &headers,
+                &text(lines),
+            )
+            .await
+        }
+        Transport::Udp => Ok(datagrams(&config.address, lines).await?),
+        Transport::File => append(&config.path, lines).await,
+    }
+}
+
+async fn append(path: &str, lines: &[Vec<u8>]) -> Result<(), Error> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+
+    file.write_all(&text(lines)).await?;
+    file.flush().await?;
+
+    Ok(())
+}
+
+// tag values escape commas, equals signs and spaces
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            ',' | '=' | ' ' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            // a line break would end the line
+            '\n' | '\r' => escaped.push_str("\\ "),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::tests::report;
+
+    #[test]
+    fn line_protocol() {
+        assert_eq!(
+            render(&report(), "my pc"),
+            [
+                "temperature,host=my\\ pc,hardware_type=Cpu,hardware=AMD\\ Ryzen\\ 7\\,\\ \"X3D\"\\=1,sensor=Core\\ #1\\ a\\=b\\,\\ \"hot\",identifier=/amdcpu/0/temperature/2 value=54.5 1700000000250000000",
+                "load,host=my\\ pc,hardware_type=Cpu,hardware=AMD\\ Ryzen\\ 7\\,\\ \"X3D\"\\=1,sensor=CPU\\ Total,identifier=/amdcpu/0/load/0 value=12 1700000000250000000",
+            ]
+        );
+
+        // empty tags are left out
+        assert!(render(&report(), "")[1].starts_with("load,hardware_type=Cpu,"));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("a b,c=d"), "a\\ b\\,c\\=d");
+        assert_eq!(escape("\"quoted\""), "\"quoted\"");
+        assert_eq!(escape("two\nlines\r"), "two\\ lines\\ ");
+    }
+}
@@ -0,0 +1,464 @@
+use std::fmt::Display;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+
+use crate::http;
+use crate::system::{Report, SensorType};
+
+mod graphite;
+mod influx;
+mod otlp;
+mod statsd;
+
+// lines written per request, what influxdb recommends
+const BATCH_LINES: usize = 5000;
+
+// otlp writes a message per hardware component instead of a line per sensor
+const BATCH_COMPONENTS: usize = 100;
+
+// bytes kept while the destination is unreachable, the oldest lines or messages are dropped beyond this,
+// counted in bytes since an otlp message holds a whole component and a line a single sensor
+const MAXIMUM_PENDING_BYTES: usize = 64 * 1024 * 1024;
+
+// the first retry waits this long, doubling after every failure up to the maximum
+const MINIMUM_BACKOFF_SECONDS: u64 = 5;
+const MAXIMUM_BACKOFF_SECONDS: u64 = 300;
+
+// receivers drop larger datagrams, so udp batches are split to stay under a typical mtu
+const MAXIMUM_DATAGRAM_BYTES: usize = 1400;
+
+// how long connecting and writing may take before the batch is retried later
+const WRITE_TIMEOUT_SECONDS: u64 = 10;
+
+// the latest report, written on the interval
+type Reports = watch::Receiver<Option<Arc<Report>>>;
+
+// error type
+#[derive(Debug)]
+pub(crate) enum Error {
+    // the destination could not be reached, the batch is retried
+    Io(io::Error),
+    // the destination refused the batch, retrying would not help
+    Rejected(String),
+}
+
+// implement from for io error
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// implement from for http error, a url that cannot be posted to is never retried
+impl From<http::Error> for Error {
+    fn from(error: http::Error) -> Self {
+        match error {
+            http::Error::Io(error) => Self::Io(error),
+            http::Error::Url(reason) => Self::Rejected(reason),
+        }
+    }
+}
+
+// implement display for error
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Rejected(reason) => write!(f, "Rejected: {}", reason),
+        }
+    }
+}
+
+// the outputs readings can be written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Kind {
+    Influx,
+    Graphite,
+    Statsd,
+    Otlp,
+}
+
+impl Kind {
+    pub const ALL: [Self; 4] = [Self::Influx, Self::Graphite, Self::Statsd, Self::Otlp];
+}
+
+// the names shown in settings
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Influx => "InfluxDB",
+                Self::Graphite => "Graphite",
+                Self::Statsd => "StatsD",
+                Self::Otlp => "OpenTelemetry",
+            }
+        )
+    }
+}
+
+// every output, each is turned on separately
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SinkConfig {
+    pub(crate) influx: influx::InfluxConfig,
+    pub(crate) graphite: graphite::GraphiteConfig,
+    pub(crate) statsd: statsd::StatsdConfig,
+    pub(crate) otlp: otlp::OtlpConfig,
+}
+
+impl SinkConfig {
+    pub(crate) fn enabled(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Influx => self.influx.enabled,
+            Kind::Graphite => self.graphite.enabled,
+            Kind::Statsd => self.statsd.enabled,
+            Kind::Otlp => self.otlp.enabled,
+        }
+    }
+
+    pub(crate) fn set_enabled(&mut self, kind: Kind, enabled: bool) {
+        match kind {
+            Kind::Influx => self.influx.enabled = enabled,
+            Kind::Graphite => self.graphite.enabled = enabled,
+            Kind::Statsd => self.statsd.enabled = enabled,
+            Kind::Otlp => self.otlp.enabled = enabled,
+        }
+    }
+
+    // where the output writes to, shown in settings while it is off
+    pub(crate) fn destination(&self, kind: Kind) -> String {
+        match kind {
+            Kind::Influx => self.influx.destination(),
+            Kind::Graphite => self.graphite.address.clone(),
+            Kind::Statsd => format!("udp://{}", self.statsd.address),
+            Kind::Otlp => self.otlp.endpoint.clone(),
+        }
+    }
+}
+
+// an output with its own config, writing until dropped
+#[derive(Debug)]
+pub(crate) struct Sink {
+    pub(crate) kind: Kind,
+    task: JoinHandle<()>,
+    status: watch::Receiver<String>,
+}
+
+impl Sink {
+    // what was last written or went wrong, shown in settings
+    pub(crate) fn status(&self) -> String {
+        self.status.borrow().clone()
+    }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// asynchronously start writing the readings to an output on its interval
+pub(crate) async fn start(kind: Kind, config: SinkConfig, reports: Reports) -> Arc<Sink> {
+    let output = match kind {
+        Kind::Influx => Output::Influx(config.influx),
+        Kind::Graphite => Output::Graphite(config.graphite),
+        Kind::Statsd => Output::Statsd(config.statsd),
+        Kind::Otlp => Output::Otlp(config.otlp),
+    };
+    let (status, receiver) = watch::channel(format!("Writing to {}", output.destination()));
+
+    Arc::new(Sink {
+        kind,
+        task: tokio::spawn(run(output, reports, status)),
+        status: receiver,
+    })
+}
+
+// an output and the config it writes with
+enum Output {
+    Influx(influx::InfluxConfig),
+    Graphite(graphite::GraphiteConfig),
+    Statsd(statsd::StatsdConfig),
+    Otlp(otlp::OtlpConfig),
+}
+
+impl Output {
+    fn destination(&self) -> String {
+        match self {
+            Self::Influx(config) => config.destination(),
+            Self::Graphite(config) => config.address.clone(),
+            Self::Statsd(config) => format!("udp://{}", config.address),
+            Self::Otlp(config) => config.endpoint.clone(),
+        }
+    }
+
+    fn interval_seconds(&self) -> u64 {
+        match self {
+            Self::Influx(config) => config.interval_seconds,
+            Self::Graphite(config) => config.interval_seconds,
+            Self::Statsd(config) => config.interval_seconds,
+            Self::Otlp(config) => config.interval_seconds,
+        }
+    }
+
+    // how many of what render returns are written at once
+    fn batch(&self) -> usize {
+        match self {
+            Self::Otlp(_) => BATCH_COMPONENTS,
+            _ => BATCH_LINES,
+        }
+    }
+
+    // a line per sensor, or for otlp an encoded message per hardware component
+    fn render(&self, report: &Report, host: &str) -> Vec<Vec<u8>> {
+        let lines = match self {
+            Self::Influx(_) => influx::render(report, host),
+            Self::Graphite(config) => graphite::render(report, host, &config.prefix),
+            Self::Statsd(config) => statsd::render(config, report, host),
+            Self::Otlp(_) => return otlp::render(report, host),
+        };
+
+        lines.into_iter().map(String::into_bytes).collect()
+    }
+
+    async fn write(&self, batch: &[Vec<u8>]) -> Result<(), Error> {
+        let write = async {
+            match self {
+                Self::Influx(config) => influx::write(config, batch).await,
+                Self::Graphite(config) => graphite::write(config, batch).await,
+                Self::Statsd(config) => statsd::write(config, batch).await,
+                Self::Otlp(config) => otlp::write(config, batch).await,
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(WRITE_TIMEOUT_SECONDS), write)
+            .await
+            .unwrap_or_else(|_| Err(io::Error::from(io::ErrorKind::TimedOut).into()))
+    }
+}
+
+async fn run(output: Output, reports: Reports, status: watch::Sender<String>) {
+    let host = host();
+
+    let mut ticker = interval(Duration::from_secs(output.interval_seconds().max(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    // rendered but not yet written, oldest first
+    let mut pending = Vec::new();
+    let mut written = None;
+    let mut backoff = Duration::ZERO;
+    let mut retry_at = Instant::now();
+
+    loop {
+        ticker.tick().await;
+
+        let latest = reports.borrow().clone();
+
+        // the same report is not written twice, e.g. while updates are paused
+        if let Some(report) = latest.filter(|report| Some(report.timestamp) != written) {
+            written = Some(report.timestamp);
+            pending.extend(output.render(&report, &host));
+            pending.drain(..overflow(&pending, MAXIMUM_PENDING_BYTES));
+        }
+
+        if Instant::now() < retry_at {
+            continue;
+        }
+
+        while !pending.is_empty() {
+            let batch = pending.len().min(output.batch());
+
+            match output.write(&pending[..batch]).await {
+                Ok(()) => {
+                    pending.drain(..batch);
+                    backoff = Duration::ZERO;
+                    status.send_replace(format!(
+                        "Wrote to {} at {}",
+                        output.destination(),
+                        Local::now().format("%H:%M:%S")
+                    ));
+                }
+                // the batch is dropped so it does not block the ones after it
+                Err(Error::Rejected(reason)) => {
+                    pending.drain(..batch);
+                    status.send_replace(format!(
+                        "{} rejected a batch: {}",
+                        output.destination(),
+                        reason
+                    ));
+                }
+                Err(error) => {
+                    backoff = (backoff * 2).clamp(
+                        Duration::from_secs(MINIMUM_BACKOFF_SECONDS),
+                        Duration::from_secs(MAXIMUM_BACKOFF_SECONDS),
+                    );
+                    retry_at = Instant::now() + backoff;
+                    status.send_replace(format!("{}, retrying in {} s", error, backoff.as_secs()));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// every line ending in a line break
+fn text(lines: &[Vec<u8>]) -> Vec<u8> {
+    let mut text = lines.join(&b'\n');
+    text.push(b'\n');
+    text
+}
+
+// as few datagrams as possible, without splitting a line
+async fn datagrams(address: &str, lines: &[Vec<u8>]) -> io::Result<()> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(address).await?;
+
+    let mut datagram = Vec::new();
+
+    for line in lines {
+        if !datagram.is_empty() && datagram.len() + line.len() + 1 > MAXIMUM_DATAGRAM_BYTES {
+            socket.send(&datagram).await?;
+            datagram.clear();
+        }
+
+        datagram.extend_from_slice(line);
+        datagram.push(b'\n');
+    }
+
+    if !datagram.is_empty() {
+        socket.send(&datagram).await?;
+    }
+
+    Ok(())
+}
+
+// how many of the oldest lines or messages to drop to keep at most the maximum bytes
+fn overflow(pending: &[Vec<u8>], maximum: usize) -> usize {
+    let mut excess = pending
+        .iter()
+        .map(Vec::len)
+        .sum::<usize>()
+        .saturating_sub(maximum);
+
+    pending
+        .iter()
+        .take_while(|item| {
+            let drop = excess > 0;
+            excess = excess.saturating_sub(item.len());
+            drop
+        })
+        .count()
+}
+
+// the destinations are expected to run on this machine or the local network
+async fn post(
+    url: &str,
+    content_type: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> Result<(), Error> {
+    let response = http::post(url, content_type, headers, body).await?;
+
+    match response.status {
+        200..=299 => Ok(()),
+        // the destination may be starting, overloaded or out of space, try again later
+        429 | 500..=599 => Err(Error::Io(io::Error::other(format!(
+            "{} answered {}",
+            url, response.status
+        )))),
+        _ => Err(Error::Rejected(format!(
+            "{} answered {}: {}",
+            url, response.status, response.body
+        ))),
+    }
+}
+
+// names this machine in the written series
+fn host() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| String::from("localhost"))
+}
+
+// the measurement or metric name of a sensor type
+fn measurement(sensor_type: SensorType) -> &'static str {
+    match sensor_type {
+        SensorType::Voltage => "voltage",
+        SensorType::Current => "current",
+        SensorType::Power => "power",
+        SensorType::Clock => "clock",
+        SensorType::Temperature => "temperature",
+        SensorType::Load => "load",
+        SensorType::Frequency => "frequency",
+        SensorType::Fan => "fan",
+        SensorType::Flow => "flow",
+        SensorType::Control => "control",
+        SensorType::Level => "level",
+        SensorType::Factor => "factor",
+        SensorType::Data => "data",
+        SensorType::SmallData => "small_data",
+        SensorType::Throughput => "throughput",
+        SensorType::TimeSpan => "time_span",
+        SensorType::Energy => "energy",
+        SensorType::Noise => "noise",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::system::{Hardware, HardwareType, Sensor};
+
+    // a cpu with names that need escaping in every format, shared by the encoder tests
+    pub(super) fn report() -> Report {
+        let sensor = |sensor_type, identifier: &str, name: &str, value| Sensor {
+            sensor_type,
+            identifier: String::from(identifier),
+            name: String::from(name),
+            index: 0,
+            value,
+            max: value,
+            min: value,
+        };
+
+        Report {
+            timestamp: Local.timestamp_opt(1_700_000_000, 250_000_000).unwrap(),
+            hardware: vec![Hardware {
+                hardware_type: HardwareType::Cpu,
+                identifier: String::from("/amdcpu/0"),
+                name: String::from("AMD Ryzen 7, \"X3D\"=1"),
+                sensors: vec![
+                    sensor(
+                        SensorType::Temperature,
+                        "/amdcpu/0/temperature/2",
+                        "Core #1 a=b, \"hot\"",
+                        54.5,
+                    ),
+                    sensor(SensorType::Load, "/amdcpu/0/load/0", "CPU Total", 12_f32),
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn overflow_drops_the_oldest() {
+        let pending = vec![vec![0; 10], vec![0; 20], vec![0; 30]];
+
+        assert_eq!(overflow(&pending, 60), 0);
+        assert_eq!(overflow(&pending, 59), 1);
+        assert_eq!(overflow(&pending, 50), 1);
+        assert_eq!(overflow(&pending, 49), 2);
+        assert_eq!(overflow(&pending, 0), 3);
+        assert_eq!(overflow(&[], 0), 0);
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sink::{post, Error};
+use crate::system::{Hardware, Report, SensorType};
+
+// names this app in the resource and the instrumentation scope
+const SERVICE_NAME: &str = "corroded_monitor";
+
+// where and how often the sensors are exported as otlp metrics, over http with protobuf
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct OtlpConfig {
+    pub(crate) enabled: bool,
+    // the collector's otlp/http metrics endpoint
+    pub(crate) endpoint: String,
+    // sent with every export, e.g. an api key for a hosted collector
+    pub(crate) headers: BTreeMap<String, String>,
+    pub(crate) interval_seconds: u64,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::from("http://localhost:4318/v1/metrics"),
+            headers: BTreeMap::new(),
+            interval_seconds: 10,
+        }
+    }
+}
+
+// the metric a sensor type is exported as, with its ucum unit
+fn metric(sensor_type: SensorType) -> (&'static str, &'static str, &'static str) {
+    match sensor_type {
+        SensorType::Voltage => ("hw.voltage", "V", "Voltage"),
+        SensorType::Current => ("hw.current", "A", "Current"),
+        SensorType::Power => ("hw.power", "W", "Power draw"),
+        SensorType::Clock => ("hw.clock", "MHz", "Clock speed"),
+        SensorType::Temperature => ("hw.temperature", "Cel", "Temperature"),
+        SensorType::Load => ("hw.load", "%", "Load"),
+        SensorType::Frequency => ("hw.frequency", "Hz", "Frequency"),
+        SensorType::Fan => ("hw.fan.speed", "{rpm}", "Fan speed"),
+        SensorType::Flow => ("hw.flow", "L/h", "Flow rate"),
+        SensorType::Control => ("hw.fan.control", "%", "Fan control"),
+        SensorType::Level => ("hw.level", "%", "Level"),
+        SensorType::Factor => ("hw.factor", "1", "Unitless factor"),
+        SensorType::Data => ("hw.data", "GiBy", "Data"),
+        SensorType::SmallData => ("hw.data.small", "MiBy", "Data"),
+        SensorType::Throughput => ("hw.throughput", "By/s", "Throughput"),
+        SensorType::TimeSpan => ("hw.time_span", "s", "Time span"),
+        SensorType::Energy => ("hw.energy", "mW.h", "Energy"),
+        SensorType::Noise => ("hw.noise", "dB", "Noise, A-weighted"),
+    }
+}
+
+// an encoded resource_metrics field per hardware component, concatenated they form an
+// ExportMetricsServiceRequest, so batches are written without decoding them again
+pub(super) fn render(report: &Report, host: &str) -> Vec<Vec<u8>> {
+    let timestamp = report.timestamp.timestamp_nanos_opt().unwrap_or_default() as u64;
+
+    report
+        .hardware
+        .iter()
+        .filter(|hardware| !hardware.sensors.is_empty())
+        .map(|hardware| {
+            let mut request = Vec::new();
+            message(
+                &mut request,
+                1,
+                &resource_metrics(hardware, host, timestamp),
+            );
+            request
+        })
+        .collect()
+}
+
+pub(super) async fn write(config: &OtlpConfig, components: &[Vec<u8>]) -> Result<(), Error> {
+    let headers = config
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<Vec<_>>();
+
+    post(
+        &config.endpoint,
+        "application/x-protobuf",
+        &headers,
+        &components.concat(),
+    )
+    .await
+}
+
+// ResourceMetrics, the hardware component is the resource, its sensors grouped by metric
+fn resource_metrics(hardware: &Hardware, host: &str, timestamp: u64) -> Vec<u8> {
+    let mut resource = Vec::new();
+
+    for (key, value) in [
+        ("service.name", SERVICE_NAME),
+        ("service.version", env!("CARGO_PKG_VERSION")),
+        ("host.name", host),
+        ("hw.id", &hardware.identifier),
+        ("hw.name", &hardware.name),
+        ("hw.type", &hardware.hardware_type.to_string()),
+    ] {
+        message(&mut resource, 1, &attribute(key, value));
+    }
+
+    // the data points of each metric, in the order the sensor types first appear
+    let mut metrics: Vec<(SensorType, Vec<u8>)> = Vec::new();
+
+    for sensor in &hardware.sensors {
+        let mut point = Vec::new();
+        message(&mut point, 7, &attribute("sensor", &sensor.name));
+        message(&mut point, 7, &attribute("identifier", &sensor.identifier));
+        fixed64(&mut point, 3, timestamp);
+        fixed64(&mut point, 4, f64::from(sensor.value).to_bits());
+
+        match metrics
+            .iter_mut()
+            .find(|(sensor_type, _)| *sensor_type == sensor.sensor_type)
+        {
+            Some((_, gauge)) => message(gauge, 1, &point),
+            None => {
+                let mut gauge = Vec::new();
+                message(&mut gauge, 1, &point);
+                metrics.push((sensor.sensor_type, gauge));
+            }
+        }
+    }
+
+    let mut scope = Vec::new();
+    string(&mut scope, 1, SERVICE_NAME);
+    string(&mut scope, 2, env!("CARGO_PKG_VERSION"));
+
+    let mut scope_metrics = Vec::new();
+    message(&mut scope_metrics, 1, &scope);
+
+    for (sensor_type, gauge) in metrics {
+        let (name, unit, description) = metric(sensor_type);
+
+        let mut metric = Vec::new();
+        string(&mut metric, 1, name);
+        string(&mut metric, 2, description);
+        string(&mut metric, 3, unit);
+        message(&mut metric, 5, &gauge);
+
+        message(&mut scope_metrics, 2, &metric);
+    }
+
+    let mut resource_metrics = Vec::new();
+    message(&mut resource_metrics, 1, &resource);
+    message(&mut resource_metrics, 2, &scope_metrics);
+    resource_metrics
+}
+
+// KeyValue with a string AnyValue
+fn attribute(key: &str, value: &str) -> Vec<u8> {
+    let mut any = Vec::new();
+    string(&mut any, 1, value);
+
+    let mut attribute = Vec::new();
+    string(&mut attribute, 1, key);
+    message(&mut attribute, 2, &any);
+    attribute
+}
+
+// the few protobuf wire types otlp metrics need
+
+fn varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+
+    buffer.push(value as u8);
+}
+
+fn fixed64(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    varint(buffer, field << 3 | 1);
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn message(buffer: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(buffer, field << 3 | 2);
+    varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+fn string(buffer: &mut Vec<u8>, field: u64, text: &str) {
+    message(buffer, field, text.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::tests::report;
+
+    // a decoded field, varints are not used by the encoder
+    #[derive(Debug, PartialEq)]
+    enum Value<'a> {
+        Fixed64(u64),
+        Bytes(&'a [u8]),
+    }
+
+    fn read_varint(bytes: &mut &[u8]) -> u64 {
+        let mut value = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = bytes[0];
+            *bytes = &bytes[1..];
+            value |= u64::from(byte & 0x7f) << shift;
+
+            if byte < 0x80 {
+                break;
+            }
+        }
+
+        value
+    }
+
+    // every field of a message in order, panicking on anything the encoder does not write
+    fn fields(mut bytes: &[u8]) -> Vec<(u64, Value<'_>)> {
+        let mut fields = Vec::new();
+
+        while !bytes.is_empty() {
+            let key = read_varint(&mut bytes);
+
+            let value = match key & 7 {
+                1 => {
+                    let (value, rest) = bytes.split_at(8);
+                    bytes = rest;
+                    Value::Fixed64(u64::from_le_bytes(value.try_into().unwrap()))
+                }
+                2 => {
+                    let length = read_varint(&mut bytes) as usize;
+                    let (value, rest) = bytes.split_at(length);
+                    bytes = rest;
+                    Value::Bytes(value)
+                }
+                wire_type => panic!("unexpected wire type {}", wire_type),
+            };
+
+            fields.push((key >> 3, value));
+        }
+
+        fields
+    }
+
+    // the length delimited fields with the given number
+    fn messages(bytes: &[u8], field: u64) -> Vec<&[u8]> {
+        fields(bytes)
+            .into_iter()
+            .filter_map(|(number, value)| match value {
+                Value::Bytes(bytes) if number == field => Some(bytes),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn text(bytes: &[u8], field: u64) -> &str {
+        std::str::from_utf8(messages(bytes, field)[0]).unwrap()
+    }
+
+    // KeyValue pairs with string values
+    fn attributes(bytes: &[u8], field: u64) -> Vec<(&str, &str)> {
+        messages(bytes, field)
+            .into_iter()
+            .map(|attribute| (text(attribute, 1), text(messages(attribute, 2)[0], 1)))
+            .collect()
+    }
+
+    #[test]
+    fn wire_types() {
+        let mut buffer = Vec::new();
+        varint(&mut buffer, 0);
+        varint(&mut buffer, 127);
+        varint(&mut buffer, 128);
+        varint(&mut buffer, 300);
+        assert_eq!(buffer, [0x00, 0x7f, 0x80, 0x01, 0xac, 0x02]);
+
+        let mut buffer = Vec::new();
+        varint(&mut buffer, u64::MAX);
+        assert_eq!(
+            buffer,
+            [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+
+        let mut buffer = Vec::new();
+        fixed64(&mut buffer, 4, 54.5_f64.to_bits());
+        assert_eq!(buffer, [0x21, 0, 0, 0, 0, 0, 0x40, 0x4b, 0x40]);
+
+        let mut buffer = Vec::new();
+        string(&mut buffer, 2, "a=b, \"c\"");
+        assert_eq!(buffer, b"\x12\x08a=b, \"c\"");
+
+        // lengths of 128 bytes and more take two bytes
+        let mut buffer = Vec::new();
+        message(&mut buffer, 1, &[0; 200]);
+        assert_eq!(buffer[..3], [0x0a, 0xc8, 0x01]);
+        assert_eq!(buffer.len(), 203);
+    }
+
+    #[test]
+    fn key_value() {
+        assert_eq!(
+            attribute("hw.name", "Ryzen 7"),
+            b"\x0a\x07hw.name\x12\x09\x0a\x07Ryzen 7"
+        );
+    }
+
+    #[test]
+    fn export_request() {
+        let mut report = report();
+        report.hardware.push(Hardware {
+            hardware_type: crate::system::HardwareType::Storage,
+            identifier: String::from("/nvme/0"),
+            name: String::from("Disk"),
+            sensors: Vec::new(),
+        });
+
+        // hardware without sensors is left out
+        let components = render(&report, "my pc");
+        assert_eq!(components.len(), 1);
+
+        let request = fields(&components[0]);
+        assert_eq!(request.len(), 1);
+        assert_eq!(request[0].0, 1);
+
+        let resource_metrics = messages(&components[0], 1)[0];
+        let resource = messages(resource_metrics, 1)[0];
+        assert_eq!(
+            attributes(resource, 1),
+            [
+                ("service.name", "corroded_monitor"),
+                ("service.version", env!("CARGO_PKG_VERSION")),
+                ("host.name", "my pc"),
+                ("hw.id", "/amdcpu/0"),
+                ("hw.name", "AMD Ryzen 7, \"X3D\"=1"),
+                ("hw.type", "Cpu"),
+            ]
+        );
+
+        let scope_metrics = messages(resource_metrics, 2)[0];
+        let scope = messages(scope_metrics, 1)[0];
+        assert_eq!(text(scope, 1), "corroded_monitor");
+        assert_eq!(text(scope, 2), env!("CARGO_PKG_VERSION"));
+
+        let metrics = messages(scope_metrics, 2);
+        let expected = [
+            (
+                "hw.temperature",
+                "Temperature",
+                "Cel",
+                "Core #1 a=b, \"hot\"",
+                "/amdcpu/0/temperature/2",
+                54.5,
+            ),
+            (
+                "hw.load",
+                "Load",
+                "%",
+                "CPU Total",
+                "/amdcpu/0/load/0",
+                12.0,
+            ),
+        ];
+        assert_eq!(metrics.len(), expected.len());
+
+        for (metric, (name, description, unit, sensor, identifier, value)) in
+            metrics.into_iter().zip(expected)
+        {
+            assert_eq!(text(metric, 1), name);
+            assert_eq!(text(metric, 2), description);
+            assert_eq!(text(metric, 3), unit);
+
+            let points = messages(messages(metric, 5)[0], 1);
+            assert_eq!(points.len(), 1);
+            assert_eq!(
+                attributes(points[0], 7),
+                [("sensor", sensor), ("identifier", identifier)]
+            );
+            assert_eq!(
+                fields(points[0])[2..],
+                [
+                    (3, Value::Fixed64(1_700_000_000_250_000_000)),
+                    (4, Value::Fixed64(f64::to_bits(value))),
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::alert::wildcard;
+use crate::sink::graphite::segment;
+use crate::sink::{datagrams, measurement, Error};
+use crate::system::Report;
+
+// where and how often the sensors are sent as statsd gauges
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct StatsdConfig {
+    pub(crate) enabled: bool,
+    pub(crate) address: String,
+    // the first part of every metric name
+    pub(crate) prefix: String,
+    // send hardware and sensor names as dogstatsd tags instead of in the metric name
+    pub(crate) dogstatsd: bool,
+    // the share of gauges sent each interval, between 0 and 1
+    pub(crate) sample_rate: f32,
+    // sensor identifiers or globs to send, every sensor when empty, e.g. /amdcpu/0/temperature/*
+    pub(crate) sensors: Vec<String>,
+    pub(crate) interval_seconds: u64,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: String::from("127.0.0.1:8125"),
+            prefix: String::from("corroded_monitor"),
+            dogstatsd: false,
+            sample_rate: 1.0,
+            sensors: Vec::new(),
+            interval_seconds: 10,
+        }
+    }
+}
+
+// a gauge per sampled sensor, e.g. corroded_monitor.cpu.amd_ryzen_7_5800x.temperature.cpu_package:54.5|g
+// or with dogstatsd, corroded_monitor.temperature:54.5|g|#host:pc,hardware_type:cpu,...
+pub(super) fn render(config: &StatsdConfig, report: &Report, host: &str) -> Vec<String> {
+    let sample_rate = config.sample_rate.clamp(0.0, 1.0);
+    let mut lines = Vec::new();
+
+    for hardware in &report.hardware {
+        for sensor in &hardware.sensors {
+            let allowed = config.sensors.is_empty()
+                || config
+                    .sensors
+                    .iter()
+                    .any(|pattern| wildcard(pattern, &sensor.identifier));
+
+            if !allowed || !sampled(sample_rate) {
+                continue;
+            }
+
+            let mut line = if config.dogstatsd {
+                format!(
+                    "{}.{}:{}|g",
+                    config.prefix,
+                    measurement(sensor.sensor_type),
+                    sensor.value
+                )
+            } else {
+                format!(
+                    "{}.{}.{}.{}.{}:{}|g",
+                    config.prefix,
+                    segment(&hardware.hardware_type.to_string()),
+                    segment(&hardware.name),
+                    measurement(sensor.sensor_type),
+                    segment(&sensor.name),
+                    sensor.value
+                )
+            };
+
+            // the server scales counts by the rate, gauges only need it to be known
+            if sample_rate < 1.0 {
+                line.push_str(&format!("|@{}", sample_rate));
+            }
+
+            if config.dogstatsd {
+                let tags = [
+                    ("host", host.to_string()),
+                    ("hardware_type", hardware.hardware_type.to_string()),
+                    ("hardware", hardware.name.clone()),
+                    ("sensor", sensor.name.clone()),
+                    ("identifier", sensor.identifier.clone()),
+                ];
+
+                line.push_str("|#");
+                line.push_str(
+                    &tags
+                        .iter()
+                        .map(|(key, value)| format!("{}:{}", key, tag(value)))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+            }
+
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+// fire and forget, a lost datagram is not worth sending again
+pub(super) async fn write(config: &StatsdConfig, lines: &[Vec<u8>]) -> Result<(), Error> {
+    datagrams(&config.address, lines)
+        .await
+        .map_err(|error| Error::Rejected(error.to_string()))
+}
+
+// whether a gauge is sent this interval
+fn sampled(sample_rate: f32) -> bool {
+    if sample_rate >= 1.0 {
+        return true;
+    }
+
+    // every random state is seeded differently, which is random enough for sampling
+    let random = RandomState::new().build_hasher().finish();
+
+    (random as f64 / u64::MAX as f64) < sample_rate as f64
+}
+
+// commas separate tags and pipes separate fields, so neither may be in a value
+fn tag(value: &str) -> String {
+    value
+        .chars()
+        .map(|character| match character {
+            ',' | '|' | '#' | '\n' => '_',
+            _ => character,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::tests::report;
+
+    #[test]
+    fn gauges() {
+        assert_eq!(
+            render(&StatsdConfig::default(), &report(), "my pc"),
+            [
+                "corroded_monitor.cpu.amd_ryzen_7_x3d_1.temperature.core_1_a_b_hot:54.5|g",
+                "corroded_monitor.cpu.amd_ryzen_7_x3d_1.load.cpu_total:12|g",
+            ]
+        );
+    }
+
+    #[test]
+    fn dogstatsd_tags() {
+        let config = StatsdConfig {
+            dogstatsd: true,
+            ..StatsdConfig::default()
+        };
+
+        assert_eq!(
+            render(&config, &report(), "my pc"),
+            [
+                "corroded_monitor.temperature:54.5|g|#host:my pc,hardware_type:Cpu,hardware:AMD Ryzen 7_ \"X3D\"=1,sensor:Core _1 a=b_ \"hot\",identifier:/amdcpu/0/temperature/2",
+                "corroded_monitor.load:12|g|#host:my pc,hardware_type:Cpu,hardware:AMD Ryzen 7_ \"X3D\"=1,sensor:CPU Total,identifier:/amdcpu/0/load/0",
+            ]
+        );
+    }
+
+    #[test]
+    fn filtered_and_sampled() {
+        let config = StatsdConfig {
+            sensors: vec![String::from("/amdcpu/0/load/*")],
+            ..StatsdConfig::default()
+        };
+        assert_eq!(
+            render(&config, &report(), "my pc"),
+            ["corroded_monitor.cpu.amd_ryzen_7_x3d_1.load.cpu_total:12|g"]
+        );
+
+        let config = StatsdConfig {
+            sample_rate: 0.0,
+            ..StatsdConfig::default()
+        };
+        assert!(render(&config, &report(), "my pc").is_empty());
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(tag("a,b|c#d\ne f:g"), "a_b_c_d_e f:g");
+    }
+}
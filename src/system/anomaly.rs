@@ -0,0 +1,272 @@
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use chrono::{DateTime, Local};
+use iced::alignment::Vertical;
+use iced::widget::{
+    button, column, container, horizontal_space, pick_list, row, scrollable, text, vertical_space,
+};
+use iced::{theme, Alignment, Element, Length};
+
+use crate::system::history::History;
+use crate::system::{Report, SensorType, SystemStats};
+use crate::ui::chart::TimeWindow;
+use crate::ui::style::button::SettingsButton;
+use crate::ui::style::container::GraphBox;
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::{Message, Route};
+
+// how much of the baseline each new reading makes up, roughly the last 40 readings count
+const ALPHA: f32 = 0.05;
+
+// readings a sensor has to have before it can be flagged, so the baseline has settled
+const WARMUP: u32 = 60;
+
+// how many standard deviations from the mean a reading has to be to be flagged
+const THRESHOLD: f32 = 6_f32;
+
+// the number of anomalies kept, the oldest are dropped first
+const CAPACITY: usize = 500;
+
+// the standard deviation never counts as smaller than this, so sensors which barely move are not
+// flagged for tiny changes, sensor types without one are not watched since they swing with load
+fn minimum_deviation(sensor_type: SensorType) -> Option<f32> {
+    match sensor_type {
+        SensorType::Temperature => Some(2.5),
+        SensorType::Fan => Some(100_f32),
+        SensorType::Voltage => Some(0.05),
+        SensorType::Flow => Some(10_f32),
+        _ => None,
+    }
+}
+
+// the normal range of a sensor, an exponentially weighted mean and variance
+#[derive(Debug, Clone, Default)]
+struct Baseline {
+    mean: f32,
+    variance: f32,
+    count: u32,
+    // set while the sensor is outside its normal range, so each excursion is flagged once
+    flagged: bool,
+}
+
+impl Baseline {
+    // learn the reading, returns how many standard deviations it was from the mean if it is an outlier
+    fn push(&mut self, value: f32, minimum_deviation: f32) -> Option<f32> {
+        if self.count == 0 {
+            self.mean = value;
+        }
+
+        let deviation = (value - self.mean) / self.variance.sqrt().max(minimum_deviation);
+        let mut outlier = None;
+
+        if self.count >= WARMUP {
+            if deviation.abs() >= THRESHOLD {
+                if !self.flagged {
+                    outlier = Some(deviation);
+                }
+
+                self.flagged = true;
+            } else if deviation.abs() < THRESHOLD / 2_f32 {
+                self.flagged = false;
+            }
+        }
+
+        let difference = value - self.mean;
+        self.mean += ALPHA * difference;
+        self.variance = (1_f32 - ALPHA) * (self.variance + ALPHA * difference * difference);
+        self.count = self.count.saturating_add(1);
+
+        outlier
+    }
+}
+
+// a reading outside the normal range of its sensor
+#[derive(Debug, Clone)]
+pub(crate) struct Anomaly {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) identifier: String,
+    pub(crate) hardware: String,
+    pub(crate) sensor: String,
+    pub(crate) sensor_type: SensorType,
+    pub(crate) value: f32,
+    // the mean before the reading
+    pub(crate) expected: f32,
+    // standard deviations from the mean, negative when below it
+    pub(crate) deviation: f32,
+}
+
+// the anomalies page, learns the normal range of each sensor and lists the readings outside it
+#[derive(Debug, Clone)]
+pub(crate) struct Anomalies {
+    // keyed by sensor identifier
+    baselines: HashMap<String, Baseline>,
+    // newest last
+    events: VecDeque<Anomaly>,
+    // the sensor whose graph is shown
+    pub(crate) selected: Option<String>,
+    pub(crate) time_window: TimeWindow,
+}
+
+impl Anomalies {
+    pub(crate) fn new() -> Self {
+        Self {
+            baselines: HashMap::new(),
+            events: VecDeque::new(),
+            selected: None,
+            time_window: TimeWindow::TenMinutes,
+        }
+    }
+
+    // check every watched sensor in the report, marking outliers on their history and hardware graphs
+    pub(crate) fn evaluate(
+        &mut self,
+        report: &Report,
+        history: &mut History,
+        stats: &mut SystemStats,
+    ) {
+        for hardware in &report.hardware {
+            for sensor in &hardware.sensors {
+                let Some(minimum_deviation) = minimum_deviation(sensor.sensor_type) else {
+                    continue;
+                };
+
+                let baseline = self.baselines.entry(sensor.identifier.clone()).or_default();
+                let expected = baseline.mean;
+
+                let Some(deviation) = baseline.push(sensor.value, minimum_deviation) else {
+                    continue;
+                };
+
+                history.mark(&sensor.identifier, sensor.value, report.timestamp);
+                stats.mark(&hardware.identifier, sensor, report.timestamp);
+
+                self.events.push_back(Anomaly {
+                    timestamp: report.timestamp,
+                    identifier: sensor.identifier.clone(),
+                    hardware: hardware.name.clone(),
+                    sensor: sensor.name.clone(),
+                    sensor_type: sensor.sensor_type,
+                    value: sensor.value,
+                    expected,
+                    deviation,
+                });
+
+                if self.events.len() > CAPACITY {
+                    self.events.pop_front();
+                }
+
+                // show the first anomaly without having to pick it
+                if self.selected.is_none() {
+                    self.selected = Some(sensor.identifier.clone());
+                }
+            }
+        }
+    }
+
+    // forget the anomalies, the baselines are kept
+    pub(crate) fn clear(&mut self, history: &mut History, stats: &mut SystemStats) {
+        self.events.clear();
+        self.selected = None;
+        history.clear_markers();
+        stats.clear_markers();
+    }
+
+    pub(crate) fn view_large<'a>(
+        &'a self,
+        history: &'a History,
+        enabled: bool,
+    ) -> Element<'a, Message> {
+        let sensor = self
+            .selected
+            .as_ref()
+            .and_then(|identifier| history.get(identifier));
+
+        let graph: Element<Message> = match sensor {
+            Some(sensor) => column!(
+                text(format!(
+                    "{} / {} ({})",
+                    sensor.hardware_name, sensor.name, sensor.sensor_type
+                ))
+                .size(14),
+                container(sensor.graph.view(self.time_window))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(theme::Container::Custom(Box::new(GraphBox::new((
+                        0, 255, 255,
+                    ))))),
+            )
+            .spacing(5)
+            .height(Length::FillPortion(3))
+            .into(),
+            None => text(if enabled {
+                "No anomalies so far"
+            } else {
+                "Anomaly detection is off, it can be turned on in the settings"
+            })
+            .size(16)
+            .height(Length::FillPortion(3))
+            .into(),
+        };
+
+        // newest first
+        let events = self.events.iter().rev().map(|anomaly| {
+            let unit = anomaly.sensor_type.unit();
+
+            Element::from(
+                button(
+                    text(format!(
+                        "{}  {} / {}  {:.2} {} (usually {:.2} {}, {:+.1} σ)",
+                        anomaly.timestamp.format("%H:%M:%S"),
+                        anomaly.hardware,
+                        anomaly.sensor,
+                        anomaly.value,
+                        unit,
+                        anomaly.expected,
+                        unit,
+                        anomaly.deviation
+                    ))
+                    .size(14),
+                )
+                .on_press(Message::AnomalySelected(anomaly.identifier.clone()))
+                .style(theme::Button::Custom(Box::new(SettingsButton)))
+                .width(Length::Fill)
+                .padding(5),
+            )
+        });
+
+        column!(
+            // the title bar
+            row!(
+                text("Anomalies")
+                    .vertical_alignment(Vertical::Center)
+                    .size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Anomalies, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                button(text("Clear").size(14))
+                    .on_press(Message::AnomaliesCleared)
+                    .style(theme::Button::Custom(Box::new(SettingsButton)))
+                    .padding(5),
+                horizontal_space(Length::Fill),
+                text(format!("{} anomalies", self.events.len())),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
+            vertical_space(Length::Fixed(20_f32)),
+            graph,
+            vertical_space(Length::Fixed(20_f32)),
+            scrollable(column(events.collect()).spacing(5)).height(Length::FillPortion(2)),
+        )
+        .padding(20)
+        .into()
+    }
+}
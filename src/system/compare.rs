@@ -0,0 +1,183 @@
+use std::rc::Rc;
+
+use iced::alignment::Vertical;
+use iced::widget::{column, container, horizontal_space, pick_list, row, text, vertical_space};
+use iced::{theme, Alignment, Element, Length};
+
+use crate::system::SystemStats;
+use crate::ui::chart::{overlay, overlay_scale, snapshot, Series, Snapshot, TimeWindow};
+use crate::ui::export::save_button;
+use crate::ui::style::container::GraphBox;
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::{Message, Route};
+
+// colors given to the overlaid graphs in order
+const COLORS: [(u8, u8, u8); 6] = [
+    (0, 255, 255),
+    (255, 190, 125),
+    (119, 221, 119),
+    (195, 177, 225),
+    (183, 53, 90),
+    (255, 255, 125),
+];
+
+// possible graph types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GraphState {
+    Temperature,
+    Utilization,
+}
+
+impl GraphState {
+    pub const ALL: [Self; 2] = [Self::Temperature, Self::Utilization];
+}
+
+// the text for the pick list
+impl std::fmt::Display for GraphState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Temperature => "Temperature",
+                Self::Utilization => "Utilization",
+            }
+        )
+    }
+}
+
+// the compare page, overlays the same kind of graph from every component
+#[derive(Debug, Clone)]
+pub(crate) struct Compare {
+    pub(crate) graph_state: GraphState,
+    pub(crate) time_window: TimeWindow,
+}
+
+impl Compare {
+    pub(crate) fn new() -> Self {
+        Self {
+            graph_state: GraphState::Temperature,
+            time_window: TimeWindow::TenMinutes,
+        }
+    }
+
+    // one series for each component which has the selected graph
+    fn series<'a>(&self, stats: &'a SystemStats) -> Vec<Series<'a>> {
+        let mut graphs = Vec::new();
+
+        match self.graph_state {
+            GraphState::Temperature => {
+                graphs.push((String::from("CPU"), &stats.cpu.temperature_graph));
+
+                for gpu in &stats.gpus {
+                    graphs.push((format!("GPU {}", gpu.index), &gpu.temperature_graph));
+                }
+
+                for disk in &stats.disks {
+                    graphs.push((format!("Disk {}", disk.index), &disk.temperature_graph));
+                }
+            }
+            GraphState::Utilization => {
+                graphs.push((String::from("CPU"), &stats.cpu.load_graph));
+
+                for gpu in &stats.gpus {
+                    graphs.push((format!("GPU {}", gpu.index), &gpu.load_graph));
+                }
+
+                graphs.push((String::from("RAM"), &stats.ram.load_graph));
+
+                for disk in &stats.disks {
+                    graphs.push((format!("Disk {}", disk.index), &disk.activity_graph));
+                }
+            }
+        }
+
+        graphs
+            .into_iter()
+            .zip(COLORS.iter().cycle())
+            .map(|((name, graph), color)| Series::new(name, graph, *color))
+            .collect()
+    }
+
+    // the graph on the page, for saving as an image
+    pub(crate) fn snapshots(&self, stats: &SystemStats) -> Vec<Snapshot> {
+        let title = match self.graph_state {
+            GraphState::Temperature => "Temperature (°C)",
+            GraphState::Utilization => "Utilization (%)",
+        };
+
+        vec![snapshot(title, &self.series(stats), self.time_window)]
+    }
+
+    // large view of the widget, the compare page
+    pub(crate) fn view_large<'a>(
+        &'a self,
+        stats: &'a SystemStats,
+        celsius: bool,
+    ) -> Element<'a, Message> {
+        let scale = overlay_scale(&self.series(stats), self.time_window);
+
+        column!(
+            // the title bar
+            row!(
+                text("Compare")
+                    .vertical_alignment(Vertical::Center)
+                    .size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Compare, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Compare),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
+            vertical_space(Length::Fixed(20_f32)),
+            // the graph
+            column!(
+                row!(
+                    match self.graph_state {
+                        GraphState::Temperature => text(if celsius {
+                            format!("Temperature (0-{:.0}°C)", scale.end)
+                        } else {
+                            format!("Temperature (0-{:.0}°F)", scale.end * 1.8 + 32_f32)
+                        }),
+                        GraphState::Utilization => text(format!("Utilization (0-{}%)", scale.end)),
+                    }
+                    .size(14),
+                    horizontal_space(Length::Fill),
+                    pick_list(
+                        &GraphState::ALL[..],
+                        Some(self.graph_state),
+                        Message::ComparePickChanged,
+                    ) // the picklist for the different graph types
+                    .text_size(14)
+                    .width(Length::Fixed(120_f32))
+                    .padding(0)
+                    .style(theme::PickList::Custom(
+                        Rc::new(PickListStyle),
+                        Rc::new(PickListStyle),
+                    )),
+                )
+                .width(Length::Fill),
+                container(overlay(self.series(stats), self.time_window))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(theme::Container::Custom(Box::new(GraphBox::new((
+                        0, 255, 255,
+                    ))))),
+            )
+            .spacing(5)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        )
+        .padding(20)
+        .into()
+    }
+}
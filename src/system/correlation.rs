@@ -0,0 +1,123 @@
+use std::rc::Rc;
+
+use iced::alignment::Vertical;
+use iced::widget::{
+    column, container, horizontal_space, pick_list, row, text, vertical_space, Toggler,
+};
+use iced::{theme, Alignment, Element, Length};
+
+use crate::system::history::{History, SensorChoice};
+use crate::ui::chart::{scatter, TimeWindow};
+use crate::ui::style::container::GraphBox;
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::style::toggler::Toggler as TogglerStyle;
+use crate::ui::{Message, Route};
+
+// the correlation page, a scatter plot of two sensors against each other
+#[derive(Debug, Clone)]
+pub(crate) struct Correlation {
+    pub(crate) x: Option<SensorChoice>,
+    pub(crate) y: Option<SensorChoice>,
+    pub(crate) time_window: TimeWindow,
+    pub(crate) color_by_time: bool,
+}
+
+impl Correlation {
+    pub(crate) fn new() -> Self {
+        Self {
+            x: None,
+            y: None,
+            time_window: TimeWindow::TenMinutes,
+            color_by_time: true,
+        }
+    }
+
+    // large view of the widget, the correlation page
+    pub(crate) fn view_large<'a>(&'a self, history: &'a History) -> Element<'a, Message> {
+        let choices = history.choices();
+
+        // the paired samples of the two sensors, if both have been picked
+        let points = match (&self.x, &self.y) {
+            (Some(x), Some(y)) => match (history.get(&x.identifier), history.get(&y.identifier)) {
+                (Some(x), Some(y)) => x.graph.paired(&y.graph, self.time_window),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        column!(
+            // the title bar
+            row!(
+                text("Correlation")
+                    .vertical_alignment(Vertical::Center)
+                    .size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Correlation, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fill),
+                Toggler::new(
+                    Some(String::from("Color by time")),
+                    self.color_by_time,
+                    Message::CorrelationColorToggled,
+                )
+                .text_size(14)
+                .width(Length::Shrink)
+                .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
+            vertical_space(Length::Fixed(20_f32)),
+            // the sensor pickers
+            row!(
+                text("X").size(16),
+                pick_list(
+                    choices.clone(),
+                    self.x.clone(),
+                    Message::CorrelationXChanged
+                )
+                .placeholder("Pick a sensor")
+                .text_size(14)
+                .width(Length::Fill)
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                )),
+                horizontal_space(Length::Fixed(20_f32)),
+                text("Y").size(16),
+                pick_list(choices, self.y.clone(), Message::CorrelationYChanged)
+                    .placeholder("Pick a sensor")
+                    .text_size(14)
+                    .width(Length::Fill)
+                    .style(theme::PickList::Custom(
+                        Rc::new(PickListStyle),
+                        Rc::new(PickListStyle),
+                    )),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center),
+            vertical_space(Length::Fixed(20_f32)),
+            // the scatter plot
+            column!(
+                text(format!("{} paired samples", points.len())).size(14),
+                container(scatter(points, (0, 255, 255), self.color_by_time))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(theme::Container::Custom(Box::new(GraphBox::new((
+                        0, 255, 255,
+                    ))))),
+            )
+            .spacing(5)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        )
+        .padding(20)
+        .into()
+    }
+}
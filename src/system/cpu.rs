@@ -1,7 +1,7 @@
-use std::collections::VecDeque;
 use std::fmt::Display;
 use std::rc::Rc;
 
+use chrono::{DateTime, Local};
 use iced::alignment::Vertical;
 use iced::widget::{
     button, column, container, horizontal_space, pick_list, row, text, vertical_space,
@@ -10,11 +10,31 @@ use iced::{theme, Element};
 use iced::{Alignment, Length};
 use regex::Regex;
 
-use crate::system::{Data, Hardware, SensorType};
+use crate::alert::Severity;
+use crate::system::statistics::{Format, StatisticsSet, StatisticsWindow};
+use crate::system::throttling::{PowerLimit, Sample, Throttling};
+use crate::system::{Data, Hardware, Sensor, SensorType};
+use crate::ui::chart::{heatmap, heatmap_range, snapshot, LineGraph, Series, Snapshot, TimeWindow};
+use crate::ui::export::save_button;
 use crate::ui::style::button::ComponentSelect;
 use crate::ui::style::container::GraphBox;
 use crate::ui::style::pick_list::PickList as PickListStyle;
-use crate::ui::{chart::LineGraph, Message, Route};
+use crate::ui::{Message, Route};
+
+// the temperature limit used when the hardware monitor does not report the distance to it
+const DEFAULT_TJMAX: f32 = 100_f32;
+
+// sensors holding the package power limit, or the draw against it
+const POWER_LIMIT_SENSORS: [&str; 3] =
+    ["CPU PPT", "Package Power Limit", "CPU Package Power Limit"];
+
+// package temperatures, amd cpus report only these and no temperature per core
+const PACKAGE_TEMPERATURE_SENSORS: [&str; 4] = [
+    "CPU Package",
+    "Core (Tctl/Tdie)",
+    "Core (Tctl)",
+    "Core (Tdie)",
+];
 
 struct ChunkedVec<T> {
     vec: Vec<T>,
@@ -50,15 +70,20 @@ pub(crate) enum GraphState {
     Utilization,
     Frequency,
     Power,
+    Heatmap,
 }
 
 impl GraphState {
-    pub const ALL: [Self; 4] = [
+    pub const ALL: [Self; 5] = [
         Self::Temperature,
         Self::Utilization,
         Self::Frequency,
         Self::Power,
+        Self::Heatmap,
     ];
+
+    // the per thread metrics which the heatmap can show
+    pub const HEATMAP: [Self; 3] = [Self::Utilization, Self::Temperature, Self::Frequency];
 }
 
 impl Display for GraphState {
@@ -71,58 +96,46 @@ impl Display for GraphState {
                 Self::Utilization => "Utilization",
                 Self::Frequency => "Frequency",
                 Self::Power => "Power",
+                Self::Heatmap => "Heatmap",
             }
         )
     }
 }
 
-// data for a single cpu thread
+// data for a single cpu thread, the history is kept by the graphs
 #[derive(Debug, Clone)]
 struct CpuThread {
-    temperature: VecDeque<Data>,
+    temperature: Data,
     temperature_graph: LineGraph,
-    frequency: VecDeque<Data>,
+    frequency: Data,
     frequency_graph: LineGraph,
-    load: VecDeque<Data>,
+    load: Data,
     load_graph: LineGraph,
 }
 
 impl CpuThread {
     fn new() -> Self {
         Self {
-            temperature: VecDeque::with_capacity(600),
+            temperature: Data::default(),
             temperature_graph: LineGraph::new((183, 53, 90)),
-            frequency: VecDeque::with_capacity(600),
+            frequency: Data::default(),
             frequency_graph: LineGraph::new((255, 190, 125)),
-            load: VecDeque::with_capacity(600),
-            load_graph: LineGraph::new((0, 255, 255)),
+            load: Data::default(),
+            load_graph: LineGraph::new((0, 255, 255)).with_bounds(SensorType::Load.bounds()),
         }
     }
 
     // adds data point to thread
     fn push(&mut self, data: Data, sensor_type: SensorType) {
-        let array = match sensor_type {
-            SensorType::Temperature => &mut self.temperature,
-            SensorType::Frequency => &mut self.frequency,
-            SensorType::Load => &mut self.load,
+        let (latest, graph) = match sensor_type {
+            SensorType::Temperature => (&mut self.temperature, &mut self.temperature_graph),
+            SensorType::Frequency => (&mut self.frequency, &mut self.frequency_graph),
+            SensorType::Load => (&mut self.load, &mut self.load_graph),
             _ => unreachable!(),
         };
 
-        let graph = match sensor_type {
-            SensorType::Temperature => &mut self.temperature_graph,
-            SensorType::Frequency => &mut self.frequency_graph,
-            SensorType::Load => &mut self.load_graph,
-            _ => unreachable!(),
-        };
-
-        graph.push_data(data.current);
-
-        // limit data points to 10 minutes
-        if array.len() == 600 {
-            array.pop_front();
-        }
-
-        array.push_back(data);
+        graph.push_data(data.current, data.timestamp);
+        *latest = data;
     }
 }
 
@@ -131,6 +144,10 @@ impl CpuThread {
 struct CpuCore {
     threads: Vec<CpuThread>,
     thread_count: usize,
+    // degrees left before the core reaches its temperature limit, if reported
+    tjmax_distance: Option<f32>,
+    // whether the core has a temperature sensor of its own
+    has_temperature: bool,
 }
 
 impl CpuCore {
@@ -138,6 +155,8 @@ impl CpuCore {
         Self {
             threads: Vec::new(),
             thread_count: 0,
+            tjmax_distance: None,
+            has_temperature: false,
         }
     }
 
@@ -151,6 +170,8 @@ impl CpuCore {
 #[derive(Debug, Clone)]
 pub(crate) struct Cpu {
     pub(crate) name: String,
+    // identifier assigned by OHM, used to reset its minimum and maximum values
+    pub(crate) identifier: String,
     cores: Vec<CpuCore>,
     total_temperature: f32,
     total_frequency: f32,
@@ -159,14 +180,22 @@ pub(crate) struct Cpu {
     maximum_temperature: f32,
     maximum_power: Option<f32>,
     maximum_frequency: f32,
-    average_temperature: f32,
-    average_frequency: f32,
-    average_power: Option<f32>,
-    average_load: f32,
+    pub(crate) statistics: StatisticsSet,
+    // throttling episodes, found from the clocks and temperatures of the cores
+    pub(crate) throttling: Throttling,
     pub(crate) graph_state: GraphState,
-    power: VecDeque<Data>,
+    // the metric shown when the graph state is heatmap
+    pub(crate) heatmap_metric: GraphState,
+    pub(crate) time_window: TimeWindow,
+    power: Option<Data>,
+    // used for throttling when the cores have no temperature of their own
+    package_temperature: Option<f32>,
+    // none if the cpu does not report its power limit
+    power_limit: Option<PowerLimit>,
     power_graph: LineGraph,
-    load_graph: LineGraph,
+    pub(crate) load_graph: LineGraph,
+    // average temperature across all cores
+    pub(crate) temperature_graph: LineGraph,
     regex: Regex,
     core_count: usize,
     logical_processor_count: usize,
@@ -177,6 +206,7 @@ impl Cpu {
     pub(crate) fn new() -> Self {
         Self {
             name: String::new(),
+            identifier: String::new(),
             cores: Vec::new(),
             total_temperature: 0_f32,
             total_frequency: 0_f32,
@@ -185,14 +215,22 @@ impl Cpu {
             maximum_temperature: 0_f32,
             maximum_power: None,
             maximum_frequency: 0_f32,
-            average_temperature: 0_f32,
-            average_frequency: 0_f32,
-            average_power: None,
-            average_load: 0_f32,
+            statistics: StatisticsSet::new(&[
+                ("Utilization", Format::PERCENT),
+                ("Frequency", Format::scaled(0.001, 2, " GHz")),
+                ("Temperature", Format::Temperature),
+                ("Power", Format::scaled(1_f32, 0, " W")),
+            ]),
+            throttling: Throttling::new(),
             graph_state: GraphState::Utilization,
-            power: VecDeque::with_capacity(600),
+            heatmap_metric: GraphState::Utilization,
+            time_window: TimeWindow::OneMinute,
+            power: None,
+            package_temperature: None,
+            power_limit: None,
             power_graph: LineGraph::new((119, 221, 119)),
-            load_graph: LineGraph::new((0, 255, 255)),
+            load_graph: LineGraph::new((0, 255, 255)).with_bounds(SensorType::Load.bounds()),
+            temperature_graph: LineGraph::new((183, 53, 90)),
             regex: Regex::new(r"CPU Core #(\d+)(?: Thread #(\d+))?").unwrap(), // regex for parsing cpu core/thread data
             core_count: 0,
             logical_processor_count: 0,
@@ -200,30 +238,43 @@ impl Cpu {
     }
 
     // update cpu widget with new data
-    pub(crate) fn update(&mut self, hardware_data: &Hardware) {
-        self.data_parser(hardware_data);
+    pub(crate) fn update(&mut self, hardware_data: &Hardware, timestamp: DateTime<Local>) {
+        self.data_parser(hardware_data, timestamp);
 
-        self.calculate_totals();
+        self.calculate_totals(timestamp);
         self.calculate_maximums();
-        self.calculate_averages();
+        self.calculate_statistics(timestamp);
+        self.detect_throttling(timestamp);
     }
 
     // parse data for cpu from the OHM API
-    fn data_parser(&mut self, hardware_data: &Hardware) {
+    fn data_parser(&mut self, hardware_data: &Hardware, timestamp: DateTime<Local>) {
         self.name = hardware_data.name.clone();
+        self.identifier = hardware_data.identifier.clone();
 
         for sensor in &hardware_data.sensors {
-            let data = Data::from(sensor);
-
-            if sensor.name == "CPU Cores" {
-                // limit data points to 10 minutes
-                if self.power.len() == 600 {
-                    self.power.pop_front();
+            let data = Data::from(sensor, timestamp);
+
+            if POWER_LIMIT_SENSORS.contains(&sensor.name.as_str()) {
+                self.power_limit = PowerLimit::from(sensor);
+            } else if sensor.sensor_type == SensorType::Temperature
+                && PACKAGE_TEMPERATURE_SENSORS.contains(&sensor.name.as_str())
+            {
+                self.package_temperature = Some(data.current);
+            } else if sensor.name == "CPU Cores" {
+                self.power_graph.push_data(data.current, data.timestamp);
+                self.power = Some(data);
+            } else if sensor.name.starts_with("CPU Core") && sensor.name.ends_with("TjMax") {
+                // e.g. CPU Core #1 Distance to TjMax, the core may not have been added yet
+                let core_index = self
+                    .regex
+                    .captures(&sensor.name)
+                    .and_then(|captures| captures.get(1)?.as_str().parse::<usize>().ok());
+
+                if let Some(core) = core_index.and_then(|index| self.cores.get_mut(index - 1)) {
+                    core.tjmax_distance = Some(data.current);
                 }
-
-                self.power_graph.push_data(data.current);
-                self.power.push_back(data);
-            } else if sensor.name.starts_with("CPU Core") && !sensor.name.ends_with("TjMax") {
+            } else if sensor.name.starts_with("CPU Core") {
                 match sensor.sensor_type {
                     SensorType::Load => {
                         let captures = self.regex.captures(&sensor.name).unwrap();
@@ -271,6 +322,8 @@ impl Cpu {
                             self.logical_processor_count += 1;
                         }
 
+                        self.cores[sensor.index].has_temperature = true;
+
                         // temperature data is per core so assign data to all threads in the core
                         for thread in &mut self.cores[sensor.index].threads {
                             thread.push(data, SensorType::Temperature);
@@ -283,96 +336,97 @@ impl Cpu {
     }
 
     // calculate the total stats for all cores
-    fn calculate_totals(&mut self) {
+    fn calculate_totals(&mut self, timestamp: DateTime<Local>) {
         self.total_temperature = self.calculate_total_metric(|d| &d.temperature);
 
-        if !self.power.is_empty() {
-            self.total_power = Some(self.power.back().unwrap().current);
-        }
+        self.total_power = self.power.map(|power| power.current);
 
         self.total_frequency = self.calculate_total_metric(|d| &d.frequency);
         self.total_load = self.calculate_total_metric(|d| &d.load);
 
-        self.load_graph.push_data(self.total_load); // total load graph
+        self.load_graph.push_data(self.total_load, timestamp); // total load graph
+        self.temperature_graph
+            .push_data(self.total_temperature, timestamp); // average temperature graph
     }
 
     // average maximum temperature across all cores
     fn calculate_maximums(&mut self) {
         self.maximum_temperature = self.calculate_maximum_metric(|d| &d.temperature);
 
-        if !self.power.is_empty() {
-            self.maximum_power = Some(self.power.back().unwrap().maximum);
-        }
+        self.maximum_power = self.power.map(|power| power.maximum);
 
         self.maximum_frequency = self.calculate_maximum_metric(|d| &d.frequency);
     }
 
-    // average temperature across all cores
-    fn calculate_averages(&mut self) {
-        self.average_temperature = self.calculate_average_metric(|d| &d.temperature);
-        self.average_frequency = self.calculate_average_metric(|d| &d.frequency);
-        self.average_power =
-            Some(self.power.iter().map(|d| d.current).sum::<f32>() / self.power.len() as f32);
-        self.average_load = self.calculate_average_metric(|d| &d.load);
+    // add the totals to the rolling statistics
+    fn calculate_statistics(&mut self, timestamp: DateTime<Local>) {
+        self.statistics
+            .push("Utilization", self.total_load, timestamp);
+        self.statistics
+            .push("Frequency", self.total_frequency, timestamp);
+        self.statistics
+            .push("Temperature", self.total_temperature, timestamp);
+
+        if let Some(power) = self.total_power {
+            self.statistics.push("Power", power, timestamp);
+        }
     }
 
-    fn calculate_total_metric<F>(&self, metric_selector: F) -> f32
-    where
-        F: Fn(&CpuThread) -> &VecDeque<Data>,
-    {
-        self.cores
-            .iter()
-            .flat_map(|core| &core.threads)
-            .map(|thread| {
-                let metric = metric_selector(thread);
+    // the most throttled core decides whether the cpu is throttling
+    fn detect_throttling(&mut self, timestamp: DateTime<Local>) {
+        let power_ratio = self
+            .power_limit
+            .and_then(|limit| limit.ratio(self.power.map(|power| power.current)));
 
-                if !metric.is_empty() {
-                    metric.iter().last().unwrap().current
-                } else {
-                    0_f32
+        let throttled = self
+            .cores
+            .iter()
+            .filter_map(|core| {
+                // clock and temperature are per core, so every thread has the same readings
+                let thread = core.threads.first()?;
+
+                let temperature = match core.has_temperature {
+                    true => Some(thread.temperature.current),
+                    false => self.package_temperature,
+                };
+
+                Sample {
+                    clock: thread.frequency.current,
+                    maximum_clock: thread.frequency.maximum,
+                    // nothing to go by if there is no temperature at all
+                    temperature_headroom: core
+                        .tjmax_distance
+                        .or(temperature.map(|temperature| DEFAULT_TJMAX - temperature))
+                        .unwrap_or(f32::MAX),
+                    power_ratio,
                 }
+                .assess()
             })
-            .sum::<f32>()
-            / self.logical_processor_count as f32
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        self.throttling.push(throttled, timestamp);
     }
 
-    fn calculate_maximum_metric<F>(&self, metric_selector: F) -> f32
+    fn calculate_total_metric<F>(&self, metric_selector: F) -> f32
     where
-        F: Fn(&CpuThread) -> &VecDeque<Data>,
+        F: Fn(&CpuThread) -> &Data,
     {
         self.cores
             .iter()
             .flat_map(|core| &core.threads)
-            .map(|thread| {
-                let metric = metric_selector(thread);
-
-                if !metric.is_empty() {
-                    metric.iter().last().unwrap().maximum
-                } else {
-                    0_f32
-                }
-            })
+            .map(|thread| metric_selector(thread).current)
             .sum::<f32>()
             / self.logical_processor_count as f32
     }
 
-    fn calculate_average_metric<F>(&self, metric_selector: F) -> f32
+    fn calculate_maximum_metric<F>(&self, metric_selector: F) -> f32
     where
-        F: Fn(&CpuThread) -> &VecDeque<Data>,
+        F: Fn(&CpuThread) -> &Data,
     {
         self.cores
             .iter()
             .flat_map(|core| &core.threads)
-            .map(|thread| {
-                let metric = metric_selector(thread);
-                let metric_len = metric.len() as f32;
-
-                if metric_len > 0_f32 {
-                    metric.iter().map(|v| v.current).sum::<f32>() / metric_len
-                } else {
-                    0_f32
-                }
-            })
+            .map(|thread| metric_selector(thread).maximum)
             .sum::<f32>()
             / self.logical_processor_count as f32
     }
@@ -393,16 +447,9 @@ impl Cpu {
             .spacing(5)
             .into(),
             column!(
-                column!(
-                    text("Utilization").size(16),
-                    text(format!("{:.0}%", self.total_load)).size(24),
-                ),
-                column!(
-                    text("Average Utilization").size(16),
-                    text(format!("{:.0}%", self.average_load)).size(24),
-                ),
+                text("Utilization").size(16),
+                text(format!("{:.0}%", self.total_load)).size(24)
             )
-            .spacing(5)
             .into(),
             column!(
                 column!(
@@ -413,10 +460,6 @@ impl Cpu {
                     text("Max Frequency").size(16),
                     text(format!("{:.2} Ghz", self.maximum_frequency / 1000_f32)).size(24),
                 ),
-                column!(
-                    text("Average Frequency").size(16),
-                    text(format!("{:.2} Ghz", self.average_frequency / 1000_f32)).size(24),
-                ),
             )
             .spacing(5)
             .into(),
@@ -439,22 +482,13 @@ impl Cpu {
                     })
                     .size(24),
                 ),
-                column!(
-                    text("Average Temperature").size(16),
-                    text(if celsius {
-                        format!("{:.0}°C", self.average_temperature)
-                    } else {
-                        format!("{:.0}°F", self.average_temperature * 1.8 + 32_f32)
-                    })
-                    .size(24),
-                ),
             )
             .spacing(5)
             .into(),
         ];
 
         // power is an optional stat, only show if it exists
-        if !self.power.is_empty() {
+        if self.power.is_some() {
             stat_items.push(
                 column!(
                     column!(
@@ -465,10 +499,6 @@ impl Cpu {
                         text("Max Power Consumption").size(16),
                         text(format!("{:.0} Watts", self.maximum_power.unwrap())).size(24),
                     ),
-                    column!(
-                        text("Average Power Consumption").size(16),
-                        text(format!("{:.0} Watts", self.average_power.unwrap())).size(24),
-                    ),
                 )
                 .spacing(5)
                 .into(),
@@ -478,11 +508,68 @@ impl Cpu {
         row(stat_items).spacing(20).into()
     }
 
-    pub(crate) fn view_small(&self, celsius: bool) -> Element<Message> {
+    // mark a reading on the graphs showing it, core temperatures also on the average
+    pub(crate) fn mark(&mut self, sensor: &Sensor, timestamp: DateTime<Local>) {
+        // the distance to tjmax is not graphed
+        let core_temperature = sensor.sensor_type == SensorType::Temperature
+            && sensor.name.starts_with("CPU Core")
+            && !sensor.name.ends_with("TjMax");
+
+        if !core_temperature {
+            return;
+        }
+
+        if let Some(core) = self.cores.get_mut(sensor.index) {
+            for thread in &mut core.threads {
+                thread.temperature_graph.mark(sensor.value, timestamp);
+            }
+
+            self.temperature_graph
+                .mark(self.total_temperature, timestamp);
+        }
+    }
+
+    pub(crate) fn clear_markers(&mut self) {
+        self.temperature_graph.clear_markers();
+
+        for thread in self.cores.iter_mut().flat_map(|core| &mut core.threads) {
+            thread.temperature_graph.clear_markers();
+        }
+    }
+
+    // the load, frequency and temperature shown under the name in the sidebar
+    pub(crate) fn summary(&self, celsius: bool) -> String {
+        if celsius {
+            format!(
+                "{:.0}%  {:.2} GHz  ({:.0}°C)",
+                self.total_load,
+                self.total_frequency / 1000_f32,
+                self.total_temperature
+            )
+        } else {
+            format!(
+                "{:.0}%  {:.2} GHz  ({:.0}°F)",
+                self.total_load,
+                self.total_frequency / 1000_f32,
+                self.total_temperature * 1.8 + 32_f32
+            )
+        }
+    }
+
+    pub(crate) fn load(&self) -> f32 {
+        self.total_load
+    }
+
+    // in celsius
+    pub(crate) fn temperature(&self) -> f32 {
+        self.total_temperature
+    }
+
+    pub(crate) fn view_small(&self, celsius: bool, alert: Option<Severity>) -> Element<Message> {
         button(
             row!(
                 horizontal_space(Length::Fixed(5_f32)),
-                container(self.load_graph.view())
+                container(self.load_graph.thumbnail())
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
                         0, 255, 255,
                     )))))
@@ -492,43 +579,99 @@ impl Cpu {
                 column!(
                     text("CPU"),
                     text(&self.name).size(14),
-                    text(if celsius {
-                        format!(
-                            "{:.0}%  {:.2} GHz  ({:.0}°C)",
-                            self.total_load,
-                            self.total_frequency / 1000_f32,
-                            self.total_temperature
-                        )
-                    } else {
-                        format!(
-                            "{:.0}%  {:.2} GHz  ({:.0}°F)",
-                            self.total_load,
-                            self.total_frequency / 1000_f32,
-                            self.total_temperature * 1.8 + 32_f32
-                        )
-                    })
-                    .size(14),
+                    text(self.summary(celsius)).size(14),
                 )
                 .spacing(2)
             )
             .align_items(Alignment::Center),
         )
         .on_press(Message::Navigate(Route::Cpu))
-        .style(theme::Button::Custom(Box::new(ComponentSelect)))
+        .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
         .width(Length::Fill)
         .height(Length::Fixed(75_f32))
         .into()
     }
 
-    pub(crate) fn view_large(&self, celsius: bool) -> Element<Message> {
+    // the graph of every thread for the metric shown on the heatmap
+    fn heatmap_rows(&self) -> Vec<&LineGraph> {
+        self.cores
+            .iter()
+            .flat_map(|core| &core.threads)
+            .map(|thread| match self.heatmap_metric {
+                GraphState::Temperature => &thread.temperature_graph,
+                GraphState::Frequency => &thread.frequency_graph,
+                _ => &thread.load_graph,
+            })
+            .collect()
+    }
+
+    // the graphs on the page, for saving as an image
+    pub(crate) fn snapshots(&self) -> Vec<Snapshot> {
+        // the heatmap is saved as a graph for each thread
+        let state = match self.graph_state {
+            GraphState::Power => {
+                return vec![snapshot(
+                    "Power Consumption (Watts)",
+                    &[Series::new("", &self.power_graph, (119, 221, 119))],
+                    self.time_window,
+                )]
+            }
+            GraphState::Heatmap => self.heatmap_metric,
+            state => state,
+        };
+
+        let (title, color) = match state {
+            GraphState::Temperature => ("Temperature (°C)", (183, 53, 90)),
+            GraphState::Frequency => ("Core Frequency (Mhz)", (255, 190, 125)),
+            _ => ("Utilization (%)", (0, 255, 255)),
+        };
+
+        self.cores
+            .iter()
+            .flat_map(|core| &core.threads)
+            .enumerate()
+            .map(|(index, thread)| {
+                let graph = match state {
+                    GraphState::Temperature => &thread.temperature_graph,
+                    GraphState::Frequency => &thread.frequency_graph,
+                    _ => &thread.load_graph,
+                };
+
+                snapshot(
+                    format!("Thread {} {}", index, title),
+                    &[Series::new("", graph, color)],
+                    self.time_window,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn view_large(
+        &self,
+        celsius: bool,
+        statistics_window: StatisticsWindow,
+        resettable: bool,
+    ) -> Element<Message> {
         let graph = if self.graph_state == GraphState::Power {
             // single graph for power
-            container(self.power_graph.view())
+            container(self.power_graph.view(self.time_window))
                 .style(theme::Container::Custom(Box::new(GraphBox::new((
                     119, 221, 119,
                 )))))
                 .width(Length::Fill)
                 .height(Length::Fill)
+        } else if self.graph_state == GraphState::Heatmap {
+            // every thread in one graph, a row for each logical processor
+            container(heatmap(
+                self.heatmap_rows(),
+                (0, 255, 255),
+                self.time_window,
+            ))
+            .style(theme::Container::Custom(Box::new(GraphBox::new((
+                0, 255, 255,
+            )))))
+            .width(Length::Fill)
+            .height(Length::Fill)
         } else {
             let thread_count = self
                 .cores
@@ -539,7 +682,7 @@ impl Cpu {
             let row_count = calculate_rows(thread_count);
 
             // create the graphs
-            let graphs = create_graph_elements(&self.cores, self.graph_state);
+            let graphs = create_graph_elements(&self.cores, self.graph_state, self.time_window);
 
             // create the columns of graphs
             let columns: Vec<Element<Message>> = ChunkedVec::new(graphs, row_count)
@@ -577,6 +720,39 @@ impl Cpu {
                     Rc::new(PickListStyle),
                 ))
                 .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                // picklist for the heatmap metric, only shown for the heatmap
+                if self.graph_state == GraphState::Heatmap {
+                    Element::from(
+                        row!(
+                            pick_list(
+                                &GraphState::HEATMAP[..],
+                                Some(self.heatmap_metric),
+                                Message::CpuHeatmapChanged,
+                            )
+                            .style(theme::PickList::Custom(
+                                Rc::new(PickListStyle),
+                                Rc::new(PickListStyle),
+                            ))
+                            .padding(5),
+                            horizontal_space(Length::Fixed(10_f32)),
+                        )
+                        .align_items(Alignment::Center),
+                    )
+                } else {
+                    horizontal_space(Length::Shrink).into()
+                },
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Cpu, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Cpu),
                 horizontal_space(Length::Fill),
                 text(&self.name), // name of cpu display
             )
@@ -591,8 +767,31 @@ impl Cpu {
                     GraphState::Temperature => text("Temperature"),
                     GraphState::Power => text(format!(
                         "Power Consumption (0-{} Watts)",
-                        self.power_graph.maximum_value
+                        self.power_graph.scale(self.time_window).end
                     )),
+                    GraphState::Heatmap => {
+                        let range = heatmap_range(&self.heatmap_rows(), self.time_window);
+
+                        text(match self.heatmap_metric {
+                            GraphState::Temperature if celsius => format!(
+                                "Temperature by logical processor ({:.0}-{:.0}°C)",
+                                range.start, range.end
+                            ),
+                            GraphState::Temperature => format!(
+                                "Temperature by logical processor ({:.0}-{:.0}°F)",
+                                range.start * 1.8 + 32_f32,
+                                range.end * 1.8 + 32_f32
+                            ),
+                            GraphState::Frequency => format!(
+                                "Core Frequency by logical processor ({:.0}-{:.0} Mhz)",
+                                range.start, range.end
+                            ),
+                            _ => format!(
+                                "Utilization by logical processor ({:.0}-{:.0}%)",
+                                range.start, range.end
+                            ),
+                        })
+                    }
                 }
                 .size(14),
                 graph, // the graphs
@@ -600,8 +799,14 @@ impl Cpu {
             .height(Length::Fill)
             .spacing(5)
             .width(Length::Fill),
+            vertical_space(Length::Fixed(10_f32)),
+            self.throttling.view(self.time_window),
+            vertical_space(Length::Fixed(20_f32)),
+            self.make_stats(celsius), // build the row with text stats
             vertical_space(Length::Fixed(20_f32)),
-            self.make_stats(celsius) // build the last row with text stats
+            // rolling statistics of the totals
+            self.statistics
+                .view(statistics_window, Route::Cpu, celsius, resettable),
         )
         .padding(20)
         .into()
@@ -635,7 +840,11 @@ fn calculate_rows(thread_count: usize) -> usize {
 }
 
 // create the graph elements for the cpu core graphs
-fn create_graph_elements(cores: &[CpuCore], graph_state: GraphState) -> Vec<Element<Message>> {
+fn create_graph_elements(
+    cores: &[CpuCore],
+    graph_state: GraphState,
+    time_window: TimeWindow,
+) -> Vec<Element<Message>> {
     cores
         .iter()
         .flat_map(|core| &core.threads)
@@ -647,7 +856,7 @@ fn create_graph_elements(cores: &[CpuCore], graph_state: GraphState) -> Vec<Elem
                 _ => unreachable!(),
             };
 
-            container(graph.view())
+            container(graph.view(time_window))
                 .style(theme::Container::Custom(Box::new(GraphBox::new(color))))
                 .width(Length::Fill)
                 .height(Length::Fill)
@@ -0,0 +1,196 @@
+use std::rc::Rc;
+
+use iced::alignment::Vertical;
+use iced::widget::{
+    column, container, horizontal_space, pick_list, row, text, text_input, vertical_space,
+};
+use iced::{theme, Alignment, Element, Length};
+
+use crate::system::history::{History, SensorChoice};
+use crate::ui::chart::{histogram, TimeWindow};
+use crate::ui::style::container::GraphBox;
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::{Message, Route};
+
+// the percentiles marked on the histogram
+const PERCENTILES: [(&str, f32); 3] = [("p50", 0.5), ("p95", 0.95), ("p99", 0.99)];
+
+// the distribution page, a histogram of one sensor over the time window
+#[derive(Debug, Clone)]
+pub(crate) struct Distribution {
+    pub(crate) sensor: Option<SensorChoice>,
+    pub(crate) time_window: TimeWindow,
+    // the text in the threshold input, parsed when the page is drawn
+    pub(crate) threshold: String,
+}
+
+impl Distribution {
+    pub(crate) fn new() -> Self {
+        Self {
+            sensor: None,
+            time_window: TimeWindow::TenMinutes,
+            threshold: String::new(),
+        }
+    }
+
+    // large view of the widget, the distribution page
+    pub(crate) fn view_large<'a>(&'a self, history: &'a History) -> Element<'a, Message> {
+        let sensor = self
+            .sensor
+            .as_ref()
+            .and_then(|choice| history.get(&choice.identifier));
+
+        let samples = sensor
+            .map(|sensor| sensor.graph.samples(self.time_window))
+            .unwrap_or_default();
+        let unit = sensor.map_or("", |sensor| sensor.sensor_type.unit());
+
+        let percentiles: Vec<(String, f32)> = PERCENTILES
+            .iter()
+            .filter_map(|(label, fraction)| {
+                percentile(&samples, *fraction).map(|value| (label.to_string(), value))
+            })
+            .collect();
+
+        let total = samples.iter().map(|(_, seconds)| seconds).sum::<f32>();
+
+        let mut stat_items: Vec<Element<Message>> = percentiles
+            .iter()
+            .map(|(label, value)| {
+                column!(
+                    text(label).size(16),
+                    text(format!("{:.2} {}", value, unit)).size(24)
+                )
+                .into()
+            })
+            .collect();
+
+        // how long the sensor spent above the threshold
+        if let Ok(threshold) = self.threshold.trim().parse::<f32>() {
+            let above = samples
+                .iter()
+                .filter(|(value, _)| *value > threshold)
+                .map(|(_, seconds)| seconds)
+                .sum::<f32>();
+
+            stat_items.push(
+                column!(
+                    text(format!("Above {} {}", threshold, unit)).size(16),
+                    text(format!(
+                        "{:.1}% ({})",
+                        if total > 0_f32 {
+                            above / total * 100_f32
+                        } else {
+                            0_f32
+                        },
+                        format_duration(above)
+                    ))
+                    .size(24)
+                )
+                .into(),
+            );
+        }
+
+        column!(
+            // the title bar
+            row!(
+                text("Distribution")
+                    .vertical_alignment(Vertical::Center)
+                    .size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Distribution, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
+            vertical_space(Length::Fixed(20_f32)),
+            // the sensor picker and threshold
+            row!(
+                pick_list(
+                    history.choices(),
+                    self.sensor.clone(),
+                    Message::DistributionSensorChanged
+                )
+                .placeholder("Pick a sensor")
+                .text_size(14)
+                .width(Length::Fill)
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                )),
+                horizontal_space(Length::Fixed(20_f32)),
+                text("Threshold").size(16),
+                text_input("none", &self.threshold)
+                    .on_input(Message::DistributionThresholdChanged)
+                    .size(14)
+                    .width(Length::Fixed(100_f32)),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center),
+            vertical_space(Length::Fixed(20_f32)),
+            // the histogram
+            column!(
+                text(format!(
+                    "Time spent at each value ({})",
+                    format_duration(total)
+                ))
+                .size(14),
+                container(histogram(samples, (0, 255, 255), percentiles))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(theme::Container::Custom(Box::new(GraphBox::new((
+                        0, 255, 255,
+                    ))))),
+            )
+            .spacing(5)
+            .width(Length::Fill)
+            .height(Length::Fill),
+            vertical_space(Length::Fixed(20_f32)),
+            row(stat_items).spacing(20),
+        )
+        .padding(20)
+        .into()
+    }
+}
+
+// the value below which the fraction of the weighted samples fall
+fn percentile(samples: &[(f32, f32)], fraction: f32) -> Option<f32> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let total = sorted.iter().map(|(_, weight)| weight).sum::<f32>();
+    let mut cumulative = 0_f32;
+
+    for (value, weight) in &sorted {
+        cumulative += weight;
+
+        if cumulative >= total * fraction {
+            return Some(*value);
+        }
+    }
+
+    sorted.last().map(|(value, _)| *value)
+}
+
+// seconds as 1h 02m 03s
+pub(crate) fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u64;
+
+    if seconds >= 3600 {
+        format!(
+            "{}h {:02}m {:02}s",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+use iced::alignment::Vertical;
+use iced::widget::{button, column, horizontal_space, row, scrollable, text, vertical_space};
+use iced::{theme, Alignment, Element, Length};
+
+use crate::alert::Severity;
+use crate::fleet::{Event, FleetConfig, FleetHost};
+use crate::system::{Report, SystemStats};
+use crate::ui::style::button::ComponentSelect;
+use crate::ui::Message;
+
+// how many tiles fit next to each other
+const TILES_PER_ROW: usize = 3;
+
+// the fleet page, a tile per configured host with the latest readings polled from it
+#[derive(Debug, Clone)]
+pub(crate) struct Fleet {
+    // by address
+    hosts: HashMap<String, Host>,
+}
+
+#[derive(Debug, Clone)]
+struct Host {
+    // kept to summarize it again when the temperature unit changes
+    report: Option<Arc<Report>>,
+    // from the latest report, none until the first one
+    summary: Option<Summary>,
+    updated: Option<DateTime<Local>>,
+    // why the last poll failed, the readings before it are still shown
+    error: Option<String>,
+}
+
+// what a tile shows of a report, only the latest is kept
+#[derive(Debug, Clone)]
+struct Summary {
+    // a label and summary per component
+    lines: Vec<(String, String)>,
+    // of the cpu and gpus, checked against the thresholds
+    temperatures: Vec<f32>,
+    loads: Vec<f32>,
+}
+
+impl Summary {
+    // read the report into stats like this machine's, only to summarize it
+    fn from(report: &Report, celsius: bool) -> Self {
+        let mut stats = SystemStats::new();
+        stats.apply(report);
+
+        let mut lines = vec![(String::from("CPU"), stats.cpu.summary(celsius))];
+
+        for gpu in &stats.gpus {
+            lines.push((format!("GPU {}", gpu.index), gpu.summary(celsius)));
+        }
+
+        lines.push((String::from("RAM"), stats.ram.summary()));
+
+        // the busiest disk and adapter, idle virtual adapters would crowd the tile
+        if let Some(disk) = stats
+            .disks
+            .iter()
+            .max_by(|a, b| a.throughput().total_cmp(&b.throughput()))
+        {
+            lines.push((format!("Disk {}", disk.index), disk.summary(celsius)));
+        }
+
+        if let Some(adapter) = stats
+            .network_adapters
+            .iter()
+            .max_by(|a, b| a.throughput().total_cmp(&b.throughput()))
+        {
+            lines.push((String::from("Network"), adapter.summary()));
+        }
+
+        Self {
+            lines,
+            temperatures: std::iter::once(stats.cpu.temperature())
+                .chain(stats.gpus.iter().map(|gpu| gpu.temperature()))
+                .collect(),
+            loads: std::iter::once(stats.cpu.load())
+                .chain(stats.gpus.iter().map(|gpu| gpu.load()))
+                .collect(),
+        }
+    }
+}
+
+impl Fleet {
+    pub(crate) fn new() -> Self {
+        Self {
+            hosts: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, event: Event, config: &FleetConfig, celsius: bool) {
+        // hosts removed from the config are forgotten, their polls may still arrive
+        self.hosts
+            .retain(|address, _| config.hosts.iter().any(|host| &host.address == address));
+
+        if !config
+            .hosts
+            .iter()
+            .any(|host| host.address == event.address)
+        {
+            return;
+        }
+
+        let host = self.hosts.entry(event.address).or_insert(Host {
+            report: None,
+            summary: None,
+            updated: None,
+            error: None,
+        });
+
+        match event.result {
+            Ok(report) => {
+                host.updated = Some(report.timestamp);
+                host.summary = Some(Summary::from(&report, celsius));
+                host.report = Some(report);
+                host.error = None;
+            }
+            Err(error) => host.error = Some(error),
+        }
+    }
+
+    // the summaries hold temperatures as text, so they are made again in the new unit
+    pub(crate) fn set_celsius(&mut self, celsius: bool) {
+        for host in self.hosts.values_mut() {
+            if let Some(report) = &host.report {
+                host.summary = Some(Summary::from(report, celsius));
+            }
+        }
+    }
+
+    pub(crate) fn view_large(&self, config: &FleetConfig) -> Element<Message> {
+        let reachable = config
+            .hosts
+            .iter()
+            .filter(|host| {
+                self.hosts
+                    .get(&host.address)
+                    .is_some_and(|state| state.summary.is_some() && state.error.is_none())
+            })
+            .count();
+
+        let tiles: Element<Message> = if config.hosts.is_empty() {
+            text("No hosts yet, add them to fleet.hosts in config.json")
+                .size(16)
+                .into()
+        } else {
+            let rows = config
+                .hosts
+                .iter()
+                .enumerate()
+                .collect::<Vec<_>>()
+                .chunks(TILES_PER_ROW)
+                .map(|chunk| {
+                    let mut tiles = chunk
+                        .iter()
+                        .map(|(index, host)| self.tile(*index, host, config))
+                        .collect::<Vec<_>>();
+
+                    // keeps the tiles of the last row as wide as the others
+                    while tiles.len() < TILES_PER_ROW {
+                        tiles.push(horizontal_space(Length::FillPortion(1)).into());
+                    }
+
+                    Element::from(row(tiles).spacing(10))
+                })
+                .collect::<Vec<_>>();
+
+            scrollable(column(rows).spacing(10))
+                .height(Length::Fill)
+                .into()
+        };
+
+        column!(
+            // the title bar
+            row!(
+                text("Fleet").vertical_alignment(Vertical::Center).size(28),
+                horizontal_space(Length::Fill),
+                text(format!(
+                    "{} of {} hosts reachable",
+                    reachable,
+                    config.hosts.len()
+                )),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
+            text("Select a host to open its pages, turn off Remote in the settings to return to this machine")
+                .size(14),
+            vertical_space(Length::Fixed(10_f32)),
+            tiles,
+        )
+        .padding(20)
+        .into()
+    }
+
+    // the name, status and a line per component, outlined when a threshold is crossed
+    fn tile(&self, index: usize, host: &FleetHost, config: &FleetConfig) -> Element<Message> {
+        let state = self.hosts.get(&host.address);
+        let summary = state.and_then(|state| state.summary.as_ref());
+        let error = state.and_then(|state| state.error.as_deref());
+
+        let status = match (error, state.and_then(|state| state.updated)) {
+            (Some(_), _) => String::from("Unreachable"),
+            (None, Some(updated)) => updated.format("%H:%M:%S").to_string(),
+            (None, None) => String::from("Connecting"),
+        };
+
+        let mut lines = vec![
+            Element::from(
+                row!(
+                    text(host.label()).size(20),
+                    horizontal_space(Length::Fill),
+                    text(status).size(14),
+                )
+                .align_items(Alignment::Center),
+            ),
+            text(&host.address).size(14).into(),
+            vertical_space(Length::Fixed(5_f32)).into(),
+        ];
+
+        let mut alert = None;
+
+        if let Some(summary) = summary {
+            for (label, text) in &summary.lines {
+                lines.push(line(label, text.clone()));
+            }
+
+            alert = host
+                .thresholds
+                .unwrap_or(config.thresholds)
+                .severity(&summary.temperatures, &summary.loads);
+        }
+
+        if let Some(error) = error {
+            lines.push(text(error).size(14).into());
+            // a rig that stopped answering needs a look as much as a hot one
+            alert = Some(Severity::Critical);
+        }
+
+        button(column(lines).spacing(3).padding(10))
+            .on_press(Message::FleetHostSelected(index))
+            .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
+            .width(Length::FillPortion(1))
+            .into()
+    }
+}
+
+// a component's summary next to its label
+fn line<'a>(label: &str, summary: String) -> Element<'a, Message> {
+    row!(
+        text(label).size(14).width(Length::Fixed(80_f32)),
+        text(summary).size(14),
+    )
+    .into()
+}
@@ -1,5 +1,6 @@
 use std::rc::Rc;
 
+use chrono::{DateTime, Local};
 use iced::alignment::Vertical;
 use iced::widget::{
     button, column, container, horizontal_space, pick_list, row, text, vertical_space,
@@ -7,11 +8,22 @@ use iced::widget::{
 use iced::{theme, Element};
 use iced::{Alignment, Length};
 
-use crate::system::{Data, Hardware, SensorType};
+use crate::alert::Severity;
+use crate::system::statistics::{Format, StatisticsSet, StatisticsWindow};
+use crate::system::throttling::{PowerLimit, Sample, Throttling};
+use crate::system::{Data, Hardware, Sensor, SensorType};
+use crate::ui::chart::{snapshot, LineGraph, Series, Snapshot, TimeWindow};
+use crate::ui::export::save_button;
 use crate::ui::style::button::ComponentSelect;
 use crate::ui::style::container::GraphBox;
 use crate::ui::style::pick_list::PickList as PickListStyle;
-use crate::ui::{chart::LineGraph, Message, Route};
+use crate::ui::{Message, Route};
+
+// the core temperature where gpus start to lower their clocks
+const TEMPERATURE_LIMIT: f32 = 83_f32;
+
+// the hot spot temperature where gpus start to lower their clocks
+const HOT_SPOT_LIMIT: f32 = 105_f32;
 
 // possible graph types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -152,15 +164,18 @@ impl GpuLoad {
     fn default() -> Self {
         Self {
             core: Data::default(),
-            core_graph: LineGraph::new((255, 190, 125)),
+            core_graph: LineGraph::new((255, 190, 125)).with_bounds(SensorType::Load.bounds()),
             memory: Data::default(),
-            memory_graph: LineGraph::new((255, 190, 125)),
+            memory_graph: LineGraph::new((255, 190, 125)).with_bounds(SensorType::Load.bounds()),
             frame_buffer: Data::default(),
-            frame_buffer_graph: LineGraph::new((255, 190, 125)),
+            frame_buffer_graph: LineGraph::new((255, 190, 125))
+                .with_bounds(SensorType::Load.bounds()),
             video_engine: Data::default(),
-            video_engine_graph: LineGraph::new((255, 190, 125)),
+            video_engine_graph: LineGraph::new((255, 190, 125))
+                .with_bounds(SensorType::Load.bounds()),
             bus_interface: Data::default(),
-            bus_interface_graph: LineGraph::new((255, 190, 125)),
+            bus_interface_graph: LineGraph::new((255, 190, 125))
+                .with_bounds(SensorType::Load.bounds()),
             pcie_rx: Data::default(),
             pcie_rx_graph: LineGraph::new((255, 190, 125)),
             pcie_tx: Data::default(),
@@ -173,22 +188,30 @@ impl GpuLoad {
 #[derive(Debug, Clone)]
 pub(crate) struct Gpu {
     pub(crate) name: String,
-    index: usize,
+    // identifier assigned by OHM, used to reset its minimum and maximum values
+    pub(crate) identifier: String,
+    pub(crate) index: usize,
     temperature: Data,
-    temperature_graph: LineGraph,
+    pub(crate) temperature_graph: LineGraph,
     hotspot_temperature: Data,
     hotspot_temperature_graph: LineGraph,
     fan_speed: Data,
     fan_graph: LineGraph,
     power: Data,
+    // none if the gpu does not report its power limit
+    power_limit: Option<PowerLimit>,
     power_graph: LineGraph,
     load: GpuLoad,
     memory: GpuMemory,
     clock: GpuClock,
-    load_graph: LineGraph,
+    pub(crate) load_graph: LineGraph,
     pub(crate) graph_state_1: GraphState,
     pub(crate) graph_state_2: GraphState,
     pub(crate) graph_state_3: GraphState,
+    pub(crate) time_window: TimeWindow,
+    pub(crate) statistics: StatisticsSet,
+    // throttling episodes, found from the core clock, temperatures and power
+    pub(crate) throttling: Throttling,
 }
 
 impl Gpu {
@@ -196,6 +219,7 @@ impl Gpu {
         // create a new GPU widget with default state
         Self {
             name: String::new(),
+            identifier: String::new(),
             index: 0,
             temperature: Data::default(),
             temperature_graph: LineGraph::new((255, 190, 125)),
@@ -204,112 +228,229 @@ impl Gpu {
             fan_speed: Data::default(),
             fan_graph: LineGraph::new((255, 190, 125)),
             power: Data::default(),
+            power_limit: None,
             power_graph: LineGraph::new((255, 190, 125)),
             load: GpuLoad::default(),
             memory: GpuMemory::default(),
             clock: GpuClock::default(),
-            load_graph: LineGraph::new((255, 190, 125)),
+            load_graph: LineGraph::new((255, 190, 125)).with_bounds(SensorType::Load.bounds()),
             graph_state_1: GraphState::CoreLoad,
             graph_state_2: GraphState::CoreClock,
             graph_state_3: GraphState::FanSpeed,
+            time_window: TimeWindow::OneMinute,
+            statistics: StatisticsSet::new(&[
+                ("Core Utilization", Format::PERCENT),
+                ("Memory Utilization", Format::PERCENT),
+                ("Core Frequency", Format::scaled(0.001, 2, " GHz")),
+                ("Temperature", Format::Temperature),
+                ("Hot Spot", Format::Temperature),
+                ("Power", Format::scaled(1_f32, 0, " W")),
+                ("Fan Speed", Format::scaled(1_f32, 0, " RPM")),
+            ]),
+            throttling: Throttling::new(),
         }
     }
 
     // parse data for gpu from the OHM API
-    pub fn update(&mut self, hardware_data: &Hardware, index: usize) {
+    pub fn update(&mut self, hardware_data: &Hardware, index: usize, timestamp: DateTime<Local>) {
         self.name = hardware_data.name.clone();
+        self.identifier = hardware_data.identifier.clone();
         self.index = index;
 
         for sensor in &hardware_data.sensors {
-            let data = Data::from(sensor);
+            let data = Data::from(sensor, timestamp);
 
             match sensor.name.as_str() {
                 "GPU Core" => match sensor.sensor_type {
                     SensorType::Temperature => {
-                        self.temperature_graph.push_data(data.current);
+                        self.temperature_graph
+                            .push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Temperature", data.current, data.timestamp);
                         self.temperature = data
                     }
                     SensorType::Load => {
-                        self.load.core_graph.push_data(data.current);
-                        self.load_graph.push_data(data.current);
+                        self.load.core_graph.push_data(data.current, data.timestamp);
+                        self.load_graph.push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Core Utilization", data.current, data.timestamp);
                         self.load.core = data
                     }
                     SensorType::Clock => {
-                        self.clock.core_graph.push_data(data.current);
+                        self.clock
+                            .core_graph
+                            .push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Core Frequency", data.current, data.timestamp);
                         self.clock.core = data
                     }
                     _ => {}
                 },
                 "GPU Memory" => match sensor.sensor_type {
                     SensorType::Load => {
-                        self.load.memory_graph.push_data(data.current);
+                        self.load
+                            .memory_graph
+                            .push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Memory Utilization", data.current, data.timestamp);
                         self.load.memory = data
                     }
                     SensorType::Clock => {
-                        self.clock.memory_graph.push_data(data.current);
+                        self.clock
+                            .memory_graph
+                            .push_data(data.current, data.timestamp);
                         self.clock.memory = data
                     }
                     _ => {}
                 },
                 "GPU" => {
                     if sensor.sensor_type == SensorType::Fan {
-                        self.fan_graph.push_data(data.current);
+                        self.fan_graph.push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Fan Speed", data.current, data.timestamp);
                         self.fan_speed = data
                     }
                 }
                 "GPU Frame Buffer" => {
-                    self.load.frame_buffer_graph.push_data(data.current);
+                    self.load
+                        .frame_buffer_graph
+                        .push_data(data.current, data.timestamp);
                     self.load.frame_buffer = data
                 }
                 "GPU Video Engine" => {
-                    self.load.video_engine_graph.push_data(data.current);
+                    self.load
+                        .video_engine_graph
+                        .push_data(data.current, data.timestamp);
                     self.load.video_engine = data
                 }
                 "GPU Bus" => {
-                    self.load.bus_interface_graph.push_data(data.current);
+                    self.load
+                        .bus_interface_graph
+                        .push_data(data.current, data.timestamp);
                     self.load.bus_interface = data
                 }
                 "GPU Power" => {
-                    self.power_graph.push_data(data.current);
+                    self.power_graph.push_data(data.current, data.timestamp);
+                    self.statistics.push("Power", data.current, data.timestamp);
                     self.power = data
                 }
+                "GPU Power Limit" | "GPU PPT" => self.power_limit = PowerLimit::from(sensor),
                 "GPU Memory Used" => self.memory.used = data,
                 "GPU Memory Total" => self.memory.total = data.current,
                 "GPU Memory Free" => self.memory.free = data,
                 // "GPU Shader" => {
-                //     self.clock.shader_graph.push_data(data.current);
+                //     self.clock.shader_graph.push_data(data.current, data.timestamp);
                 //     self.clock.shader = data
                 // }
                 "GPU Hot Spot" => {
-                    self.hotspot_temperature_graph.push_data(data.current);
+                    self.hotspot_temperature_graph
+                        .push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Hot Spot", data.current, data.timestamp);
                     self.hotspot_temperature = data
                 }
                 "GPU Fan 1" => {
                     if sensor.sensor_type == SensorType::Fan {
-                        self.fan_graph.push_data(data.current);
+                        self.fan_graph.push_data(data.current, data.timestamp);
+                        self.statistics
+                            .push("Fan Speed", data.current, data.timestamp);
                         self.fan_speed = data
                     }
                 }
                 "GPU PCIe Rx" => {
-                    self.load.pcie_rx_graph.push_data(data.current);
+                    self.load
+                        .pcie_rx_graph
+                        .push_data(data.current, data.timestamp);
                     self.load.pcie_rx = data
                 }
                 "GPU PCIe Tx" => {
-                    self.load.pcie_tx_graph.push_data(data.current);
+                    self.load
+                        .pcie_tx_graph
+                        .push_data(data.current, data.timestamp);
                     self.load.pcie_tx = data
                 }
                 _ => {}
             }
         }
+
+        self.detect_throttling(timestamp);
+    }
+
+    // whether the core clock is held back by the temperatures or the power draw
+    fn detect_throttling(&mut self, timestamp: DateTime<Local>) {
+        let mut temperature_headroom = TEMPERATURE_LIMIT - self.temperature.current;
+
+        // not every gpu reports a hot spot
+        if self.hotspot_temperature.current > 0_f32 {
+            temperature_headroom =
+                temperature_headroom.min(HOT_SPOT_LIMIT - self.hotspot_temperature.current);
+        }
+
+        let sample = Sample {
+            clock: self.clock.core.current,
+            maximum_clock: self.clock.core.maximum,
+            temperature_headroom,
+            power_ratio: self
+                .power_limit
+                .and_then(|limit| limit.ratio(Some(self.power.current))),
+        };
+
+        self.throttling.push(sample.assess(), timestamp);
+    }
+
+    // mark a reading on the graph showing it, if there is one
+    pub(crate) fn mark(&mut self, sensor: &Sensor, timestamp: DateTime<Local>) {
+        let graph = match (sensor.name.as_str(), sensor.sensor_type) {
+            ("GPU Core", SensorType::Temperature) => &mut self.temperature_graph,
+            ("GPU Hot Spot", _) => &mut self.hotspot_temperature_graph,
+            ("GPU" | "GPU Fan 1", SensorType::Fan) => &mut self.fan_graph,
+            _ => return,
+        };
+
+        graph.mark(sensor.value, timestamp);
+    }
+
+    pub(crate) fn clear_markers(&mut self) {
+        self.temperature_graph.clear_markers();
+        self.hotspot_temperature_graph.clear_markers();
+        self.fan_graph.clear_markers();
+    }
+
+    // the core load, clock and temperature shown under the name in the sidebar
+    pub(crate) fn summary(&self, celsius: bool) -> String {
+        if celsius {
+            format!(
+                "{:.0}%  {:.2} GHz  ({:.0}°C)",
+                self.load.core.current,
+                self.clock.core.current / 1000_f32,
+                self.temperature.current
+            )
+        } else {
+            format!(
+                "{:.0}%  {:.2} GHz  ({:.0}°F)",
+                self.load.core.current,
+                self.clock.core.current / 1000_f32,
+                self.temperature.current * 1.8 + 32_f32
+            )
+        }
+    }
+
+    pub(crate) fn load(&self) -> f32 {
+        self.load.core.current
+    }
+
+    // in celsius
+    pub(crate) fn temperature(&self) -> f32 {
+        self.temperature.current
     }
 
     // small view of the widget located in the sidebar
-    pub fn view_small(&self, celsius: bool) -> Element<Message> {
+    pub fn view_small(&self, celsius: bool, alert: Option<Severity>) -> Element<Message> {
         // the entire widget is a button
         button(
             row!(
                 horizontal_space(Length::Fixed(5_f32)),
-                container(self.load_graph.view()) // it contains the gpu load graph
+                container(self.load_graph.thumbnail()) // it contains the gpu load graph
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
                         255, 190, 125
                     )))))
@@ -320,40 +461,77 @@ impl Gpu {
                 column!(
                     text(format!("GPU {}", self.index)),
                     text(&self.name).size(14),
-                    text(if celsius {
-                        format!(
-                            "{:.0}%  {:.2} GHz  ({:.0}°C)",
-                            self.load.core.current,
-                            self.clock.core.current / 1000_f32,
-                            self.temperature.current
-                        )
-                    } else {
-                        format!(
-                            "{:.0}%  {:.2} GHz  ({:.0}°F)",
-                            self.load.core.current,
-                            self.clock.core.current / 1000_f32,
-                            self.temperature.current * 1.8 + 32_f32
-                        )
-                    })
-                    .size(14)
+                    text(self.summary(celsius)).size(14)
                 )
                 .spacing(2)
             )
             .align_items(Alignment::Center),
         )
         .on_press(Message::Navigate(Route::Gpu(self.index))) // opens the gpu page when pressed
-        .style(theme::Button::Custom(Box::new(ComponentSelect)))
+        .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
         .width(Length::Fill)
         .height(Length::Fixed(75_f32))
         .into()
     }
 
+    // the graph for a graph state and the unit of its values
+    fn graph(&self, state: GraphState) -> (&LineGraph, &'static str) {
+        match state {
+            GraphState::CoreClock => (&self.clock.core_graph, "Mhz"),
+            GraphState::MemoryClock => (&self.clock.memory_graph, "Mhz"),
+            GraphState::CoreLoad => (&self.load.core_graph, "%"),
+            GraphState::MemoryLoad => (&self.load.memory_graph, "%"),
+            GraphState::FrameBufferLoad => (&self.load.frame_buffer_graph, "%"),
+            GraphState::VideoEngineLoad => (&self.load.video_engine_graph, "%"),
+            GraphState::BusInterfaceLoad => (&self.load.bus_interface_graph, "%"),
+            GraphState::FanSpeed => (&self.fan_graph, "RPM"),
+            GraphState::Temperature => (&self.temperature_graph, "°C"),
+            GraphState::PowerUsage => (&self.power_graph, "Watts"),
+            GraphState::HotSpotTemperature => (&self.hotspot_temperature_graph, "°C"),
+            GraphState::PCIeRx => (&self.load.pcie_rx_graph, "B/s"),
+            GraphState::PCIeTx => (&self.load.pcie_tx_graph, "B/s"),
+        }
+    }
+
+    // the three graphs on the page, for saving as an image
+    pub(crate) fn snapshots(&self) -> Vec<Snapshot> {
+        [self.graph_state_1, self.graph_state_2, self.graph_state_3]
+            .into_iter()
+            .map(|state| {
+                let (graph, unit) = self.graph(state);
+
+                snapshot(
+                    format!("{} ({})", state, unit),
+                    &[Series::new("", graph, (255, 190, 125))],
+                    self.time_window,
+                )
+            })
+            .collect()
+    }
+
     // large view of the widget, the gpu page
-    pub fn view_large(&self, celsius: bool) -> Element<Message> {
+    pub fn view_large(
+        &self,
+        celsius: bool,
+        statistics_window: StatisticsWindow,
+        resettable: bool,
+    ) -> Element<Message> {
         column!(
             // the title bar
             row!(
                 text("GPU").vertical_alignment(Vertical::Center).size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Gpu(self.index), window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Gpu(self.index)),
                 horizontal_space(Length::Fill),
                 text(&self.name),
             )
@@ -368,27 +546,27 @@ impl Gpu {
                             // the label for the graph
                             GraphState::CoreLoad => text(format!(
                                 "Core Utilization (0-{}%)",
-                                self.load.core_graph.maximum_value
+                                self.load.core_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             GraphState::MemoryLoad => text(format!(
                                 "Memory Utilization (0-{}%)",
-                                self.load.memory_graph.maximum_value
+                                self.load.memory_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             GraphState::VideoEngineLoad => text(format!(
                                 "Video Engine Utilization (0-{}%)",
-                                self.load.video_engine_graph.maximum_value
+                                self.load.video_engine_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             GraphState::BusInterfaceLoad => text(format!(
                                 "Bus Interface Utilization (0-{}%)",
-                                self.load.bus_interface_graph.maximum_value
+                                self.load.bus_interface_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             GraphState::FrameBufferLoad => text(format!(
                                 "Frame Buffer Utilization (0-{}%)",
-                                self.load.frame_buffer_graph.maximum_value
+                                self.load.frame_buffer_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             _ => text(""),
@@ -411,13 +589,17 @@ impl Gpu {
                     container(
                         // the actual graph
                         match self.graph_state_1 {
-                            GraphState::CoreLoad => self.load.core_graph.view(),
-                            GraphState::MemoryLoad => self.load.memory_graph.view(),
-                            GraphState::FrameBufferLoad => self.load.frame_buffer_graph.view(),
+                            GraphState::CoreLoad => self.load.core_graph.view(self.time_window),
+                            GraphState::MemoryLoad => self.load.memory_graph.view(self.time_window),
+                            GraphState::FrameBufferLoad => {
+                                self.load.frame_buffer_graph.view(self.time_window)
+                            }
                             GraphState::BusInterfaceLoad => {
-                                self.load.bus_interface_graph.view()
+                                self.load.bus_interface_graph.view(self.time_window)
+                            }
+                            GraphState::VideoEngineLoad => {
+                                self.load.video_engine_graph.view(self.time_window)
                             }
-                            GraphState::VideoEngineLoad => self.load.video_engine_graph.view(),
                             _ => unreachable!(),
                         },
                     )
@@ -436,23 +618,23 @@ impl Gpu {
                             // the label for the graph
                             GraphState::CoreClock => text(format!(
                                 "Core Frequency (0-{}Mhz)",
-                                self.clock.core_graph.maximum_value
+                                self.clock.core_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             GraphState::MemoryClock => text(format!(
                                 "Memory Frequency (0-{}Mhz)",
-                                self.clock.memory_graph.maximum_value
+                                self.clock.memory_graph.scale(self.time_window).end
                             ))
                             .size(14),
                             // GraphState::ShaderClock => text(format!("Shader Frequency (0-{}Mhz)", self.clock.memory_graph.maximum_value)).size(14),
                             GraphState::PCIeRx => text(format!(
                                 "PCIe Down (0-{} MB/s)",
-                                self.load.pcie_rx_graph.maximum_value / 1_000_000
+                                self.load.pcie_rx_graph.scale(self.time_window).end / 1_000_000_f32
                             ))
                             .size(14),
                             GraphState::PCIeTx => text(format!(
                                 "PCIe Up (0-{} MB/s)",
-                                self.load.pcie_tx_graph.maximum_value / 1_000_000
+                                self.load.pcie_tx_graph.scale(self.time_window).end / 1_000_000_f32
                             ))
                             .size(14),
                             _ => unreachable!(),
@@ -475,11 +657,13 @@ impl Gpu {
                     container(
                         // the actual graph
                         match self.graph_state_2 {
-                            GraphState::CoreClock => self.clock.core_graph.view(),
-                            GraphState::MemoryClock => self.clock.memory_graph.view(),
-                            // GraphState::ShaderClock => self.clock.shader_graph.view(),
-                            GraphState::PCIeRx => self.load.pcie_rx_graph.view(),
-                            GraphState::PCIeTx => self.load.pcie_tx_graph.view(),
+                            GraphState::CoreClock => self.clock.core_graph.view(self.time_window),
+                            GraphState::MemoryClock => {
+                                self.clock.memory_graph.view(self.time_window)
+                            }
+                            // GraphState::ShaderClock => self.clock.shader_graph.view(self.time_window),
+                            GraphState::PCIeRx => self.load.pcie_rx_graph.view(self.time_window),
+                            GraphState::PCIeTx => self.load.pcie_tx_graph.view(self.time_window),
                             _ => unreachable!(),
                         },
                     )
@@ -500,30 +684,37 @@ impl Gpu {
                         // the label for the graph
                         GraphState::FanSpeed => text(format!(
                             "Fan Speed (0-{} RPM)",
-                            self.fan_graph.maximum_value
+                            self.fan_graph.scale(self.time_window).end
                         ))
                         .size(14),
                         GraphState::Temperature => text(if celsius {
-                            format!("Temperature (0-{}°C)", self.temperature_graph.maximum_value)
+                            format!(
+                                "Temperature (0-{}°C)",
+                                self.temperature_graph.scale(self.time_window).end
+                            )
                         } else {
-                            format!("Temperature (0-{}°F)", self.temperature_graph.maximum_value)
+                            format!(
+                                "Temperature (0-{:.0}°F)",
+                                self.temperature_graph.scale(self.time_window).end * 1.8 + 32_f32
+                            )
                         })
                         .size(14),
                         GraphState::HotSpotTemperature => text(if celsius {
                             format!(
                                 "Hot Spot Temperature (0-{}°C)",
-                                self.hotspot_temperature_graph.maximum_value
+                                self.hotspot_temperature_graph.scale(self.time_window).end
                             )
                         } else {
                             format!(
-                                "Hot Spot Temperature (0-{}°F)",
-                                self.hotspot_temperature_graph.maximum_value
+                                "Hot Spot Temperature (0-{:.0}°F)",
+                                self.hotspot_temperature_graph.scale(self.time_window).end * 1.8
+                                    + 32_f32
                             )
                         })
                         .size(14),
                         GraphState::PowerUsage => text(format!(
                             "Power Usage (0-{} Watts)",
-                            self.power_graph.maximum_value
+                            self.power_graph.scale(self.time_window).end
                         ))
                         .size(14),
                         _ => unreachable!(),
@@ -545,10 +736,12 @@ impl Gpu {
                 .width(Length::Fill),
                 container(match self.graph_state_3 {
                     // the actual graph
-                    GraphState::FanSpeed => self.fan_graph.view(),
-                    GraphState::Temperature => self.temperature_graph.view(),
-                    GraphState::HotSpotTemperature => self.hotspot_temperature_graph.view(),
-                    GraphState::PowerUsage => self.power_graph.view(),
+                    GraphState::FanSpeed => self.fan_graph.view(self.time_window),
+                    GraphState::Temperature => self.temperature_graph.view(self.time_window),
+                    GraphState::HotSpotTemperature => {
+                        self.hotspot_temperature_graph.view(self.time_window)
+                    }
+                    GraphState::PowerUsage => self.power_graph.view(self.time_window),
                     _ => unreachable!(),
                 })
                 .width(Length::Fill)
@@ -560,6 +753,8 @@ impl Gpu {
             .spacing(5)
             .width(Length::Fill)
             .height(Length::FillPortion(1)),
+            vertical_space(Length::Fixed(10_f32)),
+            self.throttling.view(self.time_window),
             vertical_space(Length::Fixed(20_f32)),
             // the text stats area
             row!(
@@ -629,7 +824,15 @@ impl Gpu {
                 )
                 .spacing(5),
             )
-            .spacing(20)
+            .spacing(20),
+            vertical_space(Length::Fixed(20_f32)),
+            // rolling statistics of the main sensors
+            self.statistics.view(
+                statistics_window,
+                Route::Gpu(self.index),
+                celsius,
+                resettable
+            ),
         )
         .padding(20)
         .into()
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+use tokio::sync::watch;
+
+use crate::system::{Report, SensorType};
+use crate::ui::chart::LineGraph;
+
+// the color used when a sensor history is drawn on its own
+const COLOR: (u8, u8, u8) = (0, 255, 255);
+
+// the history of a single sensor
+#[derive(Debug, Clone)]
+pub(crate) struct SensorHistory {
+    // the identifier of the hardware the sensor belongs to
+    pub(crate) hardware: String,
+    pub(crate) hardware_name: String,
+    pub(crate) name: String,
+    pub(crate) sensor_type: SensorType,
+    pub(crate) graph: LineGraph,
+}
+
+// a sensor which can be picked from a pick list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SensorChoice {
+    pub(crate) identifier: String,
+    label: String,
+}
+
+// the text for the pick list
+impl Display for SensorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+// the history of every sensor in the reports, keyed by sensor identifier
+#[derive(Debug, Clone, Default)]
+pub(crate) struct History {
+    sensors: BTreeMap<String, SensorHistory>,
+}
+
+impl History {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // add every reading in the report
+    pub(crate) fn push(&mut self, report: &Report) {
+        for hardware in &report.hardware {
+            for sensor in &hardware.sensors {
+                self.sensors
+                    .entry(sensor.identifier.clone())
+                    .or_insert_with(|| SensorHistory {
+                        hardware: hardware.identifier.clone(),
+                        hardware_name: hardware.name.clone(),
+                        name: sensor.name.clone(),
+                        sensor_type: sensor.sensor_type,
+                        graph: LineGraph::new(COLOR).with_bounds(sensor.sensor_type.bounds()),
+                    })
+                    .graph
+                    .push_data(sensor.value, report.timestamp);
+            }
+        }
+    }
+
+    // flag a reading on the sensor's graph
+    pub(crate) fn mark(&mut self, identifier: &str, value: f32, timestamp: DateTime<Local>) {
+        if let Some(sensor) = self.sensors.get_mut(identifier) {
+            sensor.graph.mark(value, timestamp);
+        }
+    }
+
+    pub(crate) fn clear_markers(&mut self) {
+        for sensor in self.sensors.values_mut() {
+            sensor.graph.clear_markers();
+        }
+    }
+
+    pub(crate) fn get(&self, identifier: &str) -> Option<&SensorHistory> {
+        self.sensors.get(identifier)
+    }
+
+    // every sensor as a pick list option, ordered by identifier so sensors of the same hardware stay together
+    pub(crate) fn choices(&self) -> Vec<SensorChoice> {
+        self.sensors
+            .iter()
+            .map(|(identifier, sensor)| SensorChoice {
+                identifier: identifier.clone(),
+                label: format!(
+                    "{} / {} ({})",
+                    sensor.hardware_name, sensor.name, sensor.sensor_type
+                ),
+            })
+            .collect()
+    }
+}
+
+// the history kept by the app and read by the server's json api, so there is only one
+#[derive(Debug)]
+pub(crate) struct SharedHistory {
+    history: Arc<History>,
+    // none while the app is changing it
+    published: watch::Sender<Option<Arc<History>>>,
+}
+
+impl SharedHistory {
+    pub(crate) fn new() -> Self {
+        let history = Arc::new(History::new());
+
+        Self {
+            published: watch::channel(Some(history.clone())).0,
+            history,
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Option<Arc<History>>> {
+        self.published.subscribe()
+    }
+
+    // change the history in place, it is only cloned if a request is reading it at the time
+    pub(crate) fn edit<T>(&mut self, change: impl FnOnce(&mut History) -> T) -> T {
+        self.published.send_replace(None);
+        let result = change(Arc::make_mut(&mut self.history));
+        self.published.send_replace(Some(self.history.clone()));
+
+        result
+    }
+}
+
+impl Deref for SharedHistory {
+    type Target = History;
+
+    fn deref(&self) -> &History {
+        &self.history
+    }
+}
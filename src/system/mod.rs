@@ -1,8 +1,9 @@
 use std::collections::HashMap;
-use std::ffi::{c_char, c_void, CStr};
+use std::ffi::{c_char, c_void, CStr, CString};
 use std::fmt;
 use std::sync::Arc;
 
+use chrono::{DateTime, Local};
 use serde::de::Visitor;
 use serde::{de, Deserialize, Deserializer};
 use serde_json::from_slice;
@@ -14,18 +15,32 @@ use crate::gpu::Gpu;
 use crate::ram::Ram;
 use crate::storage::Storage;
 use crate::system::network::NetworkAdapter;
-use crate::{CreateHardwareMonitor, GetReport, UpdateHardwareMonitor};
+use crate::ui::Route;
+use crate::{CreateHardwareMonitor, GetReport, ResetMinMax, UpdateHardwareMonitor};
 
+pub(crate) mod anomaly;
+pub(crate) mod compare;
+pub(crate) mod correlation;
 pub(crate) mod cpu;
+pub(crate) mod distribution;
+pub(crate) mod fleet;
 pub(crate) mod gpu;
+pub(crate) mod history;
 pub(crate) mod network;
 pub(crate) mod ram;
+pub(crate) mod statistics;
 pub(crate) mod storage;
+pub(crate) mod throttling;
+
+// the buffer the report is first read into, it grows when the report is larger
+const INITIAL_REPORT_SIZE: usize = 200000;
 
 // a wrapper around the hardware monitor reference
 #[derive(Debug)]
 pub(crate) struct HardwareMonitor {
     pub(crate) inner: *mut c_void,
+    // the size of the last report, grown whenever a report does not fit
+    report_size: usize,
 }
 
 // this is okay because the hardware monitor is always used inside a Arc<Mutex<T>>
@@ -36,7 +51,66 @@ impl HardwareMonitor {
     pub(crate) async fn new() -> Arc<Mutex<Self>> {
         spawn_blocking(|| {
             let inner = unsafe { CreateHardwareMonitor() };
-            Arc::new(Mutex::new(Self { inner }))
+            Arc::new(Mutex::new(Self {
+                inner,
+                report_size: INITIAL_REPORT_SIZE,
+            }))
+        })
+        .await
+        .unwrap()
+    }
+
+    // asynchronously read every sensor, only the report is sent to the blocking task
+    pub(crate) async fn read(monitor: Arc<Mutex<Self>>) -> Arc<Report> {
+        // fetch data from OHM API, spawn_blocking is used to prevent blocking
+        let hardware_data: serde_json::Result<Vec<Hardware>> = spawn_blocking({
+            move || {
+                let mut monitor = monitor.blocking_lock(); // lock pointer to OHM
+                let ptr = monitor.inner;
+                unsafe { UpdateHardwareMonitor(ptr) }; // update OHM
+
+                // the report is left empty when it does not fit, so it is read again into a buffer of the size it needs
+                let mut buffer: Vec<c_char> = vec![0; monitor.report_size];
+                let required = unsafe { GetReport(ptr, buffer.as_mut_ptr(), buffer.len() as i32) };
+
+                if required as usize > buffer.len() {
+                    monitor.report_size = required as usize;
+                    buffer = vec![0; monitor.report_size];
+                    unsafe { GetReport(ptr, buffer.as_mut_ptr(), buffer.len() as i32) };
+                }
+
+                let report = unsafe { CStr::from_ptr(buffer.as_ptr()) }; // convert buffer to CStr
+                from_slice(report.to_bytes()) // deserialize CStr to Vec<Hardware>
+            }
+        })
+        .await
+        .unwrap(); // unwrap thread, should never panic
+
+        let hardware = match hardware_data {
+            Ok(data) => data,
+            Err(e) => {
+                println!(
+                    "an error occurred while fetching data from OHM API: {:?}",
+                    e
+                );
+
+                Vec::new()
+            }
+        };
+
+        Arc::new(Report {
+            timestamp: Local::now(), // every sensor in the report shares this timestamp
+            hardware,
+        })
+    }
+
+    // asynchronously reset the minimum and maximum values OHM keeps for the hardware's sensors
+    pub(crate) async fn reset_min_max(monitor: Arc<Mutex<Self>>, identifier: String) {
+        spawn_blocking(move || {
+            // identifiers never contain a nul byte, an empty one matches no hardware
+            let identifier = CString::new(identifier).unwrap_or_default();
+            let ptr = monitor.blocking_lock().inner;
+            unsafe { ResetMinMax(ptr, identifier.as_ptr()) };
         })
         .await
         .unwrap()
@@ -66,97 +140,134 @@ impl SystemStats {
         }
     }
 
-    // update stats
-    pub(crate) async fn update(
-        mut self,
-        monitor: Arc<Mutex<HardwareMonitor>>,
-    ) -> Box<(Self, HashMap<String, bool>)> {
-        // fetch data from OHM API, spawn_blocking is used to prevent blocking
-        let hardware_data: serde_json::Result<Vec<Hardware>> = spawn_blocking({
-            move || {
-                let ptr = monitor.blocking_lock().inner; // lock pointer to OHM
-                unsafe { UpdateHardwareMonitor(ptr) }; // update OHM
+    // reset the statistics of the page's hardware, returns the identifier of the hardware
+    pub(crate) fn reset_statistics(&mut self, route: &Route) -> Option<String> {
+        let (statistics, identifier) = match route {
+            Route::Cpu => (&mut self.cpu.statistics, &self.cpu.identifier),
+            Route::Gpu(index) => {
+                let gpu = self.gpus.get_mut(*index)?;
+                (&mut gpu.statistics, &gpu.identifier)
+            }
+            Route::Ram => (&mut self.ram.statistics, &self.ram.identifier),
+            Route::Storage(index) => {
+                let disk = self.disks.get_mut(*index)?;
+                (&mut disk.statistics, &disk.identifier)
+            }
+            Route::Network(index) => {
+                let adapter = self.network_adapters.get_mut(*index)?;
+                (&mut adapter.statistics, &adapter.identifier)
+            }
+            _ => return None,
+        };
 
-                let mut buffer: Vec<c_char> = vec![0; 20000]; // allocate buffer for data
+        statistics.reset();
+        Some(identifier.clone())
+    }
 
-                unsafe { GetReport(ptr, buffer.as_mut_ptr(), buffer.len() as i32) }; // load data into buffer
+    // mark a reading on the graphs of the hardware it came from, by the hardware's identifier
+    pub(crate) fn mark(&mut self, hardware: &str, sensor: &Sensor, timestamp: DateTime<Local>) {
+        if self.cpu.identifier == hardware {
+            self.cpu.mark(sensor, timestamp);
+        } else if let Some(gpu) = self.gpus.iter_mut().find(|gpu| gpu.identifier == hardware) {
+            gpu.mark(sensor, timestamp);
+        } else if let Some(disk) = self
+            .disks
+            .iter_mut()
+            .find(|disk| disk.identifier == hardware)
+        {
+            disk.mark(sensor, timestamp);
+        } else if let Some(adapter) = self
+            .network_adapters
+            .iter_mut()
+            .find(|adapter| adapter.identifier == hardware)
+        {
+            adapter.mark(sensor, timestamp);
+        }
+    }
 
-                let report = unsafe { CStr::from_ptr(buffer.as_ptr()) }; // convert buffer to CStr
-                from_slice(report.to_bytes()) // deserialize CStr to Vec<Hardware>
-            }
-        })
-        .await
-        .unwrap(); // unwrap thread, should never panic
+    pub(crate) fn clear_markers(&mut self) {
+        self.cpu.clear_markers();
+        self.gpus.iter_mut().for_each(Gpu::clear_markers);
+        self.disks.iter_mut().for_each(Storage::clear_markers);
+        self.network_adapters
+            .iter_mut()
+            .for_each(NetworkAdapter::clear_markers);
+    }
 
+    // update the hardware widgets from a report, read locally or from another instance
+    // returns the names of hardware seen for the first time
+    pub(crate) fn apply(&mut self, report: &Report) -> HashMap<String, bool> {
+        let timestamp = report.timestamp;
         let mut visibility = HashMap::new();
 
-        match hardware_data {
-            Ok(data) => {
-                let mut disk_index = 0;
-                let mut gpu_index = 0;
-                let mut network_index = 0;
-
-                // iterate over hardware devices
-                for device in data {
-                    match device.hardware_type {
-                        HardwareType::Cpu => {
-                            if self.cpu.name.is_empty() {
-                                visibility.insert(device.name.clone(), true);
-                            }
-
-                            self.cpu.update(&device);
-                        }
-                        HardwareType::GpuNvidia | HardwareType::GpuAmd | HardwareType::GpuIntel => {
-                            // create new gpu if needed
-                            if self.gpus.len() == gpu_index {
-                                visibility.insert(device.name.clone(), true);
-                                self.gpus.push(Gpu::new());
-                            }
-
-                            self.gpus[gpu_index].update(&device, gpu_index);
-                            gpu_index += 1;
-                        }
-                        HardwareType::Memory => {
-                            if self.ram.name.is_empty() {
-                                visibility.insert(device.name.clone(), true);
-                            }
-
-                            self.ram.update(&device);
-                        }
-                        HardwareType::Storage => {
-                            // create new disk if needed
-                            if self.disks.len() == disk_index {
-                                visibility.insert(device.name.clone(), true);
-                                self.disks.push(Storage::new());
-                            }
-
-                            self.disks[disk_index].update(&device, disk_index);
-                            disk_index += 1;
-                        }
-                        HardwareType::Network => {
-                            // create network adapter if needed
-                            if self.network_adapters.len() == network_index {
-                                visibility.insert(device.name.clone(), true);
-                                self.network_adapters.push(NetworkAdapter::new());
-                            }
-
-                            self.network_adapters[network_index].update(&device, network_index);
-                            network_index += 1;
-                        }
-                        _ => {}
+        let mut disk_index = 0;
+        let mut gpu_index = 0;
+        let mut network_index = 0;
+
+        // iterate over hardware devices
+        for device in &report.hardware {
+            match device.hardware_type {
+                HardwareType::Cpu => {
+                    if self.cpu.name.is_empty() {
+                        visibility.insert(device.name.clone(), true);
+                    }
+
+                    self.cpu.update(device, timestamp);
+                }
+                HardwareType::GpuNvidia | HardwareType::GpuAmd | HardwareType::GpuIntel => {
+                    // create new gpu if needed
+                    if self.gpus.len() == gpu_index {
+                        visibility.insert(device.name.clone(), true);
+                        self.gpus.push(Gpu::new());
                     }
+
+                    self.gpus[gpu_index].update(device, gpu_index, timestamp);
+                    gpu_index += 1;
                 }
+                HardwareType::Memory => {
+                    if self.ram.name.is_empty() {
+                        visibility.insert(device.name.clone(), true);
+                    }
+
+                    self.ram.update(device, timestamp);
+                }
+                HardwareType::Storage => {
+                    // create new disk if needed
+                    if self.disks.len() == disk_index {
+                        visibility.insert(device.name.clone(), true);
+                        self.disks.push(Storage::new());
+                    }
+
+                    self.disks[disk_index].update(device, disk_index, timestamp);
+                    disk_index += 1;
+                }
+                HardwareType::Network => {
+                    // create network adapter if needed
+                    if self.network_adapters.len() == network_index {
+                        visibility.insert(device.name.clone(), true);
+                        self.network_adapters.push(NetworkAdapter::new());
+                    }
+
+                    self.network_adapters[network_index].update(device, network_index, timestamp);
+                    network_index += 1;
+                }
+                _ => {}
             }
-            Err(e) => println!(
-                "an error occurred while fetching data from OHM API: {:?}",
-                e
-            ),
         }
 
-        Box::new((self, visibility))
+        visibility
     }
 }
 
+// a single set of readings from the OHM API, shared with anything that consumes raw sensor data
+#[derive(Debug)]
+pub(crate) struct Report {
+    // when the readings were taken
+    pub(crate) timestamp: DateTime<Local>,
+    // every hardware component in the report
+    pub(crate) hardware: Vec<Hardware>,
+}
+
 /*
 impl Drop for SystemStats {
     fn drop(&mut self) {
@@ -172,13 +283,15 @@ impl Drop for SystemStats {
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct Hardware {
     // CPU, GPU, Memory, etc
-    hardware_type: HardwareType,
+    pub(crate) hardware_type: HardwareType,
+    // unique identifier assigned by OHM, e.g. /amdcpu/0
+    pub(crate) identifier: String,
     // a human readable name for the hardware
-    name: String,
+    pub(crate) name: String,
     // some hardware have sub-hardware
     // sub_hardware: Vec<Hardware>,
     // the sensors for this hardware
-    sensors: Vec<Sensor>,
+    pub(crate) sensors: Vec<Sensor>,
 }
 
 // a sensor for a hardware component
@@ -186,17 +299,19 @@ pub(crate) struct Hardware {
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct Sensor {
     // Temperature, Fan, Voltage, etc
-    sensor_type: SensorType,
+    pub(crate) sensor_type: SensorType,
+    // unique identifier assigned by OHM, e.g. /amdcpu/0/temperature/2
+    pub(crate) identifier: String,
     // human readable name
-    name: String,
+    pub(crate) name: String,
     // index used for components like CPU cores
-    index: usize,
+    pub(crate) index: usize,
     #[serde(deserialize_with = "deserialize_f32_or_nan_as_zero")]
     pub(crate) value: f32,
     #[serde(deserialize_with = "deserialize_f32_or_nan_as_zero")]
     pub(crate) max: f32,
-    // #[serde(deserialize_with = "deserialize_f32_or_nan_as_zero")]
-    // pub(crate) min: f32,
+    #[serde(deserialize_with = "deserialize_f32_or_nan_as_zero")]
+    pub(crate) min: f32,
 }
 
 fn deserialize_f32_or_nan_as_zero<'de, D>(deserializer: D) -> Result<f32, D::Error>
@@ -232,7 +347,7 @@ where
 }
 
 // hardware types
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum HardwareType {
     Motherboard,
     SuperIO,
@@ -278,8 +393,55 @@ impl<'de> Deserialize<'de> for HardwareType {
     }
 }
 
+// the names used when hardware types are exported
+impl fmt::Display for HardwareType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Motherboard => "Motherboard",
+                Self::SuperIO => "SuperIO",
+                Self::Cpu => "Cpu",
+                Self::Memory => "Memory",
+                Self::GpuNvidia => "GpuNvidia",
+                Self::GpuAmd => "GpuAmd",
+                Self::GpuIntel => "GpuIntel",
+                Self::Storage => "Storage",
+                Self::Network => "Network",
+                Self::Cooler => "Cooler",
+                Self::EmbeddedController => "EmbeddedController",
+                Self::Psu => "Psu",
+                Self::Battery => "Battery",
+            }
+        )
+    }
+}
+
+impl HardwareType {
+    // the hardware type with the exported name, e.g. from another instance's api
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Motherboard" => Some(Self::Motherboard),
+            "SuperIO" => Some(Self::SuperIO),
+            "Cpu" => Some(Self::Cpu),
+            "Memory" => Some(Self::Memory),
+            "GpuNvidia" => Some(Self::GpuNvidia),
+            "GpuAmd" => Some(Self::GpuAmd),
+            "GpuIntel" => Some(Self::GpuIntel),
+            "Storage" => Some(Self::Storage),
+            "Network" => Some(Self::Network),
+            "Cooler" => Some(Self::Cooler),
+            "EmbeddedController" => Some(Self::EmbeddedController),
+            "Psu" => Some(Self::Psu),
+            "Battery" => Some(Self::Battery),
+            _ => None,
+        }
+    }
+}
+
 // sensor types
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum SensorType {
     // V
     Voltage,
@@ -351,21 +513,111 @@ impl<'de> Deserialize<'de> for SensorType {
     }
 }
 
+impl SensorType {
+    // the unit values of this sensor type are reported in
+    pub(crate) fn unit(&self) -> &'static str {
+        match self {
+            Self::Voltage => "V",
+            Self::Current => "A",
+            Self::Power => "W",
+            Self::Clock => "MHz",
+            Self::Temperature => "°C",
+            Self::Load | Self::Control | Self::Level => "%",
+            Self::Frequency => "Hz",
+            Self::Fan => "RPM",
+            Self::Flow => "L/h",
+            Self::Factor => "",
+            Self::Data => "GB",
+            Self::SmallData => "MB",
+            Self::Throughput => "B/s",
+            Self::TimeSpan => "s",
+            Self::Energy => "mWh",
+            Self::Noise => "dBA",
+        }
+    }
+
+    // the sensor type with the exported name, e.g. from another instance's api
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Voltage" => Some(Self::Voltage),
+            "Current" => Some(Self::Current),
+            "Power" => Some(Self::Power),
+            "Clock" => Some(Self::Clock),
+            "Temperature" => Some(Self::Temperature),
+            "Load" => Some(Self::Load),
+            "Frequency" => Some(Self::Frequency),
+            "Fan" => Some(Self::Fan),
+            "Flow" => Some(Self::Flow),
+            "Control" => Some(Self::Control),
+            "Level" => Some(Self::Level),
+            "Factor" => Some(Self::Factor),
+            "Data" => Some(Self::Data),
+            "SmallData" => Some(Self::SmallData),
+            "Throughput" => Some(Self::Throughput),
+            "TimeSpan" => Some(Self::TimeSpan),
+            "Energy" => Some(Self::Energy),
+            "Noise" => Some(Self::Noise),
+            _ => None,
+        }
+    }
+
+    // the fixed range for graphs of percentage sensors, other graphs scale to their data
+    pub(crate) fn bounds(&self) -> Option<(f32, f32)> {
+        match self {
+            Self::Load | Self::Control | Self::Level => Some((0_f32, 100_f32)),
+            _ => None,
+        }
+    }
+}
+
+// the names used when sensor types are exported
+impl fmt::Display for SensorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Voltage => "Voltage",
+                Self::Current => "Current",
+                Self::Power => "Power",
+                Self::Clock => "Clock",
+                Self::Temperature => "Temperature",
+                Self::Load => "Load",
+                Self::Frequency => "Frequency",
+                Self::Fan => "Fan",
+                Self::Flow => "Flow",
+                Self::Control => "Control",
+                Self::Level => "Level",
+                Self::Factor => "Factor",
+                Self::Data => "Data",
+                Self::SmallData => "SmallData",
+                Self::Throughput => "Throughput",
+                Self::TimeSpan => "TimeSpan",
+                Self::Energy => "Energy",
+                Self::Noise => "Noise",
+            }
+        )
+    }
+}
+
 // used in the hardware widgets to store data
 #[derive(Default, Debug, Clone, Copy)]
 pub(crate) struct Data {
     // minimum: f32,
     maximum: f32,
     current: f32,
+    // when the sensor was read
+    timestamp: DateTime<Local>,
 }
 
 impl Data {
     // data from sensor
-    fn from(value: &Sensor) -> Self {
+    fn from(value: &Sensor, timestamp: DateTime<Local>) -> Self {
         Self {
             // minimum: value.min,
             maximum: value.max,
             current: value.value,
+            timestamp,
         }
     }
 }
@@ -1,49 +1,80 @@
-use iced::widget::{button, column, container, horizontal_space, row, text, vertical_space};
-use iced::{theme, Alignment, Element, Length};
+use std::rc::Rc;
+
+use chrono::{DateTime, Local};
 use iced::alignment::Vertical;
+use iced::widget::{
+    button, column, container, horizontal_space, pick_list, row, text, vertical_space,
+};
+use iced::{theme, Alignment, Element, Length};
 
-use crate::system::{Data, Hardware};
-use crate::ui::chart::LineGraph;
+use crate::alert::Severity;
+use crate::system::statistics::{Format, StatisticsSet, StatisticsWindow};
+use crate::system::{Data, Hardware, Sensor};
+use crate::ui::chart::{
+    overlay, overlay_scale, overlay_thumbnail, snapshot, LineGraph, Series, Snapshot, TimeWindow,
+};
+use crate::ui::export::save_button;
 use crate::ui::style::button::ComponentSelect;
 use crate::ui::style::container::GraphBox;
+use crate::ui::style::pick_list::PickList as PickListStyle;
 use crate::ui::{Message, Route};
 
 #[derive(Debug, Clone)]
 pub(crate) struct NetworkAdapter {
     pub(crate) name: String,
+    // identifier assigned by OHM, used to reset its minimum and maximum values
+    pub(crate) identifier: String,
     index: usize,
     utilization: Data,
     download_speed: Data,
     upload_speed: Data,
     downloaded: Data,
     uploaded: Data,
-    throughput_graph: LineGraph,
+    download_graph: LineGraph,
+    upload_graph: LineGraph,
+    pub(crate) time_window: TimeWindow,
+    pub(crate) statistics: StatisticsSet,
 }
 
 impl NetworkAdapter {
     pub(crate) fn new() -> Self {
         NetworkAdapter {
             name: String::new(),
+            identifier: String::new(),
             index: 0,
             utilization: Data::default(),
             download_speed: Data::default(),
             upload_speed: Data::default(),
             downloaded: Data::default(),
             uploaded: Data::default(),
-            throughput_graph: LineGraph::new((195, 177, 225)),
+            download_graph: LineGraph::new((195, 177, 225)),
+            upload_graph: LineGraph::new((119, 221, 119)),
+            time_window: TimeWindow::OneMinute,
+            statistics: StatisticsSet::new(&[
+                ("Download Speed", Format::scaled(0.000_001, 2, " MB/s")),
+                ("Upload Speed", Format::scaled(0.000_001, 2, " MB/s")),
+                ("Utilization", Format::scaled(1_f32, 1, "%")),
+            ]),
         }
     }
 
-    pub(crate) fn update(&mut self, hardware_data: &Hardware, index: usize) {
+    pub(crate) fn update(
+        &mut self,
+        hardware_data: &Hardware,
+        index: usize,
+        timestamp: DateTime<Local>,
+    ) {
         self.name = hardware_data.name.clone();
+        self.identifier = hardware_data.identifier.clone();
         self.index = index;
-        let mut throughput = 0_f32; // combined upload and download speed
 
         for sensor in &hardware_data.sensors {
-            let data = Data::from(sensor);
+            let data = Data::from(sensor, timestamp);
 
             match sensor.name.as_str() {
                 "Network Utilization" => {
+                    self.statistics
+                        .push("Utilization", data.current, data.timestamp);
                     self.utilization = data;
                 }
                 "Data Uploaded" => {
@@ -53,27 +84,76 @@ impl NetworkAdapter {
                     self.downloaded = data;
                 }
                 "Download Speed" => {
-                    throughput += data.current;
+                    self.download_graph.push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Download Speed", data.current, data.timestamp);
                     self.download_speed = data;
                 }
                 "Upload Speed" => {
-                    throughput += data.current;
+                    self.upload_graph.push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Upload Speed", data.current, data.timestamp);
                     self.upload_speed = data;
                 }
                 _ => {}
             }
         }
+    }
+
+    // download and upload speed drawn on the same graph
+    fn throughput_series(&self) -> Vec<Series> {
+        vec![
+            Series::new("Download", &self.download_graph, (195, 177, 225)),
+            Series::new("Upload", &self.upload_graph, (119, 221, 119)),
+        ]
+    }
+
+    // the graph on the page, for saving as an image
+    pub(crate) fn snapshots(&self) -> Vec<Snapshot> {
+        vec![snapshot(
+            "Throughput (B/s)",
+            &self.throughput_series(),
+            self.time_window,
+        )]
+    }
+
+    // mark a reading on the graph showing it, if there is one
+    pub(crate) fn mark(&mut self, sensor: &Sensor, timestamp: DateTime<Local>) {
+        let graph = match sensor.name.as_str() {
+            "Download Speed" => &mut self.download_graph,
+            "Upload Speed" => &mut self.upload_graph,
+            _ => return,
+        };
+
+        graph.mark(sensor.value, timestamp);
+    }
 
-        self.throughput_graph.push_data(throughput);
+    pub(crate) fn clear_markers(&mut self) {
+        self.download_graph.clear_markers();
+        self.upload_graph.clear_markers();
+    }
+
+    // the utilization and throughput shown under the name in the sidebar
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "{:.0}% {:.0} MB/s",
+            self.utilization.current,
+            (self.upload_speed.current + self.download_speed.current) / 1_000_000_f32
+        )
+    }
+
+    // bytes sent and received per second
+    pub(crate) fn throughput(&self) -> f32 {
+        self.upload_speed.current + self.download_speed.current
     }
 
     // small view of the widget located in the sidebar
-    pub fn view_small(&self) -> Element<Message> {
+    pub fn view_small(&self, alert: Option<Severity>) -> Element<Message> {
         // the entire widget is a button
         button(
             row!(
                 horizontal_space(Length::Fixed(5_f32)),
-                container(self.throughput_graph.view()) // it contains the gpu load graph
+                container(overlay_thumbnail(self.throughput_series())) // it contains the throughput graph
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
                         195, 177, 225
                     )))))
@@ -83,32 +163,43 @@ impl NetworkAdapter {
                 column!(
                     text(format!("Network {}", self.index)),
                     text(&self.name).size(14),
-                    text(format!(
-                        "{:.0}% {:.0} MB/s",
-                        self.utilization.current,
-                        (self.upload_speed.current + self.download_speed.current) / 1_000_000_f32
-                    ))
-                    .size(14),
+                    text(self.summary()).size(14),
                 )
                 .spacing(2)
             )
             .align_items(Alignment::Center),
         )
         .on_press(Message::Navigate(Route::Network(self.index))) // opens the gpu page when pressed
-        .style(theme::Button::Custom(Box::new(ComponentSelect)))
+        .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
         .width(Length::Fill)
         .height(Length::Fixed(75_f32))
         .into()
     }
 
     // large view of the widget, the network page
-    pub(crate) fn view_large(&self) -> Element<Message> {
+    pub(crate) fn view_large(
+        &self,
+        statistics_window: StatisticsWindow,
+        resettable: bool,
+    ) -> Element<Message> {
         column!(
             // the title bar
             row!(
                 text(format!("Network {}", self.index))
                     .vertical_alignment(Vertical::Center)
                     .size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Network(self.index), window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Network(self.index)),
                 horizontal_space(Length::Fill),
                 text(&self.name)
             )
@@ -119,14 +210,15 @@ impl NetworkAdapter {
             column!(
                 row!(
                     text(format!(
-                        "Throughput (0-{:.0} MB/s)",
-                        self.throughput_graph.maximum_value / 1_000_000
+                        "Throughput (0-{} MB/s)",
+                        overlay_scale(&self.throughput_series(), self.time_window).end
+                            / 1_000_000_f32
                     ))
                     .size(14),
                     horizontal_space(Length::Fill),
                 )
                 .width(Length::Fill),
-                container(self.throughput_graph.view())
+                container(overlay(self.throughput_series(), self.time_window))
                     .width(Length::Fill)
                     .height(Length::FillPortion(1))
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
@@ -168,7 +260,15 @@ impl NetworkAdapter {
                     text(format!("{:.1}%", self.utilization.current)).size(24),
                 )
             )
-            .spacing(20)
+            .spacing(20),
+            vertical_space(Length::Fixed(20_f32)),
+            // rolling statistics of the throughput
+            self.statistics.view(
+                statistics_window,
+                Route::Network(self.index),
+                false,
+                resettable
+            ),
         )
         .padding(20)
         .into()
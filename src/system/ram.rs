@@ -1,21 +1,35 @@
-use iced::widget::{button, column, container, horizontal_space, row, text, vertical_space};
-use iced::{theme, Alignment, Element, Length};
+use std::rc::Rc;
+
+use chrono::{DateTime, Local};
 use iced::alignment::Vertical;
+use iced::widget::{
+    button, column, container, horizontal_space, pick_list, row, text, vertical_space,
+};
+use iced::{theme, Alignment, Element, Length};
 
-use crate::system::{Data, Hardware};
+use crate::alert::Severity;
+use crate::system::statistics::{Format, StatisticsSet, StatisticsWindow};
+use crate::system::{Data, Hardware, SensorType};
+use crate::ui::chart::{snapshot, LineGraph, Series, Snapshot, TimeWindow};
+use crate::ui::export::save_button;
 use crate::ui::style::button::ComponentSelect;
 use crate::ui::style::container::GraphBox;
-use crate::ui::{chart::LineGraph, Message, Route};
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::{Message, Route};
 
 // ram widget
 #[derive(Debug, Clone)]
 pub(crate) struct Ram {
     pub(crate) name: String,
+    // identifier assigned by OHM, used to reset its minimum and maximum values
+    pub(crate) identifier: String,
     usage: Data,
     used: Data,
     available: Data,
     total: f32,
-    load_graph: LineGraph,
+    pub(crate) load_graph: LineGraph,
+    pub(crate) time_window: TimeWindow,
+    pub(crate) statistics: StatisticsSet,
 }
 
 impl Ram {
@@ -23,25 +37,39 @@ impl Ram {
         // ram widget with default state
         Self {
             name: String::new(),
+            identifier: String::new(),
             usage: Data::default(),
             used: Data::default(),
             available: Data::default(),
             total: 0_f32,
-            load_graph: LineGraph::new((183, 53, 90)),
+            load_graph: LineGraph::new((183, 53, 90)).with_bounds(SensorType::Load.bounds()),
+            time_window: TimeWindow::OneMinute,
+            statistics: StatisticsSet::new(&[
+                ("Utilization", Format::PERCENT),
+                ("Used", Format::scaled(1_f32, 2, " GB")),
+            ]),
         }
     }
 
     // parse data for gpu from the OHM API
-    pub(crate) fn update(&mut self, hardware_data: &Hardware) {
+    pub(crate) fn update(&mut self, hardware_data: &Hardware, timestamp: DateTime<Local>) {
+        self.identifier = hardware_data.identifier.clone();
+
         for sensor in &hardware_data.sensors {
-            let data = Data::from(sensor);
+            let data = Data::from(sensor, timestamp);
 
             match sensor.name.as_str() {
-                "Memory Used" => self.used = data,
+                "Memory Used" => {
+                    self.statistics.push("Used", data.current, data.timestamp);
+                    self.used = data;
+                }
                 "Memory Available" => self.available = data,
                 "Memory" => {
                     self.usage = data;
-                    self.load_graph.push_data(self.usage.current);
+                    self.load_graph
+                        .push_data(self.usage.current, self.usage.timestamp);
+                    self.statistics
+                        .push("Utilization", self.usage.current, self.usage.timestamp);
                 }
                 _ => {}
             }
@@ -50,50 +78,77 @@ impl Ram {
         self.total = self.used.current + self.available.current;
     }
 
+    // the used and total memory shown in the sidebar
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "{:.1}/{:.0} GB  {:.0}%",
+            self.used.current, self.total, self.usage.current
+        )
+    }
+
     // small view of the widget located in the sidebar
-    pub(crate) fn view_small(&self) -> Element<Message> {
+    pub(crate) fn view_small(&self, alert: Option<Severity>) -> Element<Message> {
         // the entire widget is a button
         button(
             row!(
                 horizontal_space(Length::Fixed(5_f32)),
-                container(self.load_graph.view()) // it contains the ram load graph
+                container(self.load_graph.thumbnail()) // it contains the ram load graph
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
                         183, 53, 90
                     )))))
                     .width(Length::Fixed(70_f32))
                     .height(Length::Fixed(60_f32)),
                 horizontal_space(Length::Fixed(10_f32)),
-                column!(
-                    text("RAM"),
-                    text(format!(
-                        "{:.1}/{:.0} GB  {:.0}%",
-                        self.used.current, self.total, self.usage.current
-                    ))
-                    .size(14),
-                )
-                .spacing(3)
+                column!(text("RAM"), text(self.summary()).size(14),).spacing(3)
             )
             .align_items(Alignment::Center),
         )
         .on_press(Message::Navigate(Route::Ram))
-        .style(theme::Button::Custom(Box::new(ComponentSelect)))
+        .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
         .width(Length::Fill)
         .height(Length::Fixed(75_f32))
         .into()
     }
 
+    // the graph on the page, for saving as an image
+    pub(crate) fn snapshots(&self) -> Vec<Snapshot> {
+        vec![snapshot(
+            "Memory Utilization (%)",
+            &[Series::new("", &self.load_graph, (183, 53, 90))],
+            self.time_window,
+        )]
+    }
+
     // large view of the widget, the ram page
-    pub(crate) fn view_large(&self) -> Element<Message> {
+    pub(crate) fn view_large(
+        &self,
+        statistics_window: StatisticsWindow,
+        resettable: bool,
+    ) -> Element<Message> {
         column!(
             // title bar
-            row!(text("RAM").vertical_alignment(Vertical::Center).size(28))
-                .align_items(Alignment::Center)
-                .height(Length::Fixed(30_f32)),
+            row!(
+                text("RAM").vertical_alignment(Vertical::Center).size(28),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Ram, window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Ram),
+            )
+            .align_items(Alignment::Center)
+            .height(Length::Fixed(30_f32)),
             vertical_space(Length::Fixed(20_f32)),
             // ram load graph
             column!(
                 text("Memory Utilization").size(14),
-                container(self.load_graph.view())
+                container(self.load_graph.view(self.time_window))
                     .width(Length::Fill)
                     .height(Length::Fill)
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
@@ -123,7 +178,11 @@ impl Ram {
                     text(format!("{:.0} GB", self.total)).size(24),
                 ),
             )
-            .spacing(20)
+            .spacing(20),
+            vertical_space(Length::Fixed(20_f32)),
+            // rolling statistics of the usage
+            self.statistics
+                .view(statistics_window, Route::Ram, false, resettable),
         )
         .padding(20)
         .into()
@@ -0,0 +1,440 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::rc::Rc;
+
+use chrono::{DateTime, Duration, Local};
+use iced::widget::{button, column, container, horizontal_space, pick_list, row, text, Row};
+use iced::{theme, Alignment, Element, Length};
+use serde::{Deserialize, Serialize};
+
+use crate::ui::style::button::SettingsButton;
+use crate::ui::style::pick_list::PickList as PickListStyle;
+use crate::ui::{Message, Route};
+
+// the longest window that needs individual samples
+const RECENT_SECONDS: i64 = 600;
+
+// neighbouring session buckets differ by 2%, which bounds the error of the session percentiles
+const SKETCH_GAMMA: f64 = 1.02;
+
+// values closer to zero than this share the zero bucket
+const SKETCH_MINIMUM: f64 = 1e-6;
+
+// keeps positive bucket indexes above zero and negative ones below
+const SKETCH_OFFSET: i32 = 10_000;
+
+// the width of each column in the statistics panel
+const COLUMN_WIDTH: f32 = 110_f32;
+
+// how far back the statistics look
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum StatisticsWindow {
+    OneMinute,
+    TenMinutes,
+    Session,
+}
+
+impl StatisticsWindow {
+    pub const ALL: [Self; 3] = [Self::OneMinute, Self::TenMinutes, Self::Session];
+
+    // the length of the window, the session has no limit
+    fn seconds(&self) -> Option<i64> {
+        match self {
+            Self::OneMinute => Some(60),
+            Self::TenMinutes => Some(600),
+            Self::Session => None,
+        }
+    }
+}
+
+// the text for the pick list
+impl Display for StatisticsWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::OneMinute => "Last 1 min",
+                Self::TenMinutes => "Last 10 min",
+                Self::Session => "Session",
+            }
+        )
+    }
+}
+
+// a summary of the samples in a window
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Statistics {
+    pub(crate) minimum: f32,
+    pub(crate) average: f32,
+    pub(crate) maximum: f32,
+    // population standard deviation
+    pub(crate) deviation: f32,
+    pub(crate) p95: f32,
+}
+
+impl Statistics {
+    // the statistics in another unit, value * scale + offset
+    fn converted(self, scale: f32, offset: f32) -> Self {
+        Self {
+            minimum: self.minimum * scale + offset,
+            average: self.average * scale + offset,
+            maximum: self.maximum * scale + offset,
+            deviation: self.deviation * scale.abs(),
+            p95: self.p95 * scale + offset,
+        }
+    }
+}
+
+// running totals since the session started, the percentiles come from a log scaled histogram
+#[derive(Debug, Clone, Default)]
+struct Session {
+    count: u64,
+    total: f64,
+    total_squares: f64,
+    minimum: f32,
+    maximum: f32,
+    sketch: BTreeMap<i32, u64>,
+}
+
+impl Session {
+    fn push(&mut self, value: f32) {
+        if self.count == 0 {
+            self.minimum = value;
+            self.maximum = value;
+        } else {
+            self.minimum = self.minimum.min(value);
+            self.maximum = self.maximum.max(value);
+        }
+
+        self.count += 1;
+        self.total += value as f64;
+        self.total_squares += value as f64 * value as f64;
+        *self.sketch.entry(sketch_index(value as f64)).or_default() += 1;
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let average = self.total / self.count as f64;
+        let variance = (self.total_squares / self.count as f64 - average * average).max(0_f64);
+
+        // the bucket holding the 95th percentile
+        let rank = (self.count as f64 * 0.95).ceil() as u64;
+        let mut cumulative = 0;
+        let p95 = self
+            .sketch
+            .iter()
+            .find(|(_, count)| {
+                cumulative += **count;
+                cumulative >= rank
+            })
+            .map_or(self.maximum, |(index, _)| sketch_value(*index) as f32);
+
+        Some(Statistics {
+            minimum: self.minimum,
+            average: average as f32,
+            maximum: self.maximum,
+            deviation: variance.sqrt() as f32,
+            p95: p95.clamp(self.minimum, self.maximum),
+        })
+    }
+}
+
+// the session bucket a value falls in, ordered the same as the values
+fn sketch_index(value: f64) -> i32 {
+    if value.abs() < SKETCH_MINIMUM || !value.is_finite() {
+        0
+    } else {
+        let index = SKETCH_OFFSET + (value.abs().ln() / SKETCH_GAMMA.ln()).ceil() as i32;
+        index * value.signum() as i32
+    }
+}
+
+// the middle of a session bucket
+fn sketch_value(index: i32) -> f64 {
+    if index == 0 {
+        return 0_f64;
+    }
+
+    let upper = SKETCH_GAMMA.powi(index.abs() - SKETCH_OFFSET);
+    let middle = upper * 2_f64 / (SKETCH_GAMMA + 1_f64);
+    middle * index.signum() as f64
+}
+
+// statistics for one metric over the recent windows and the whole session
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollingStatistics {
+    // samples from the longest window, oldest first
+    recent: VecDeque<(DateTime<Local>, f32)>,
+    session: Session,
+}
+
+impl RollingStatistics {
+    pub(crate) fn push(&mut self, value: f32, timestamp: DateTime<Local>) {
+        self.recent.push_back((timestamp, value));
+        self.session.push(value);
+
+        let oldest = timestamp - Duration::seconds(RECENT_SECONDS);
+
+        while self.recent.front().is_some_and(|(time, _)| *time <= oldest) {
+            self.recent.pop_front();
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub(crate) fn statistics(&self, window: StatisticsWindow) -> Option<Statistics> {
+        let seconds = match window.seconds() {
+            Some(seconds) => seconds,
+            None => return self.session.statistics(),
+        };
+
+        let (newest, _) = self.recent.back()?;
+        let oldest = *newest - Duration::seconds(seconds);
+
+        let mut values: Vec<f32> = self
+            .recent
+            .iter()
+            .filter(|(time, _)| *time > oldest)
+            .map(|(_, value)| *value)
+            .collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let count = values.len() as f32;
+        let average = values.iter().sum::<f32>() / count;
+        let variance = values
+            .iter()
+            .map(|value| (value - average).powi(2))
+            .sum::<f32>()
+            / count;
+
+        // nearest rank
+        let rank = ((count * 0.95).ceil() as usize).clamp(1, values.len());
+
+        Some(Statistics {
+            minimum: values[0],
+            average,
+            maximum: values[values.len() - 1],
+            deviation: variance.sqrt(),
+            p95: values[rank - 1],
+        })
+    }
+}
+
+// how the statistics of a metric are displayed
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Format {
+    // multiplied by the scale and shown with the given number of decimals and unit
+    Scaled {
+        scale: f32,
+        decimals: usize,
+        unit: &'static str,
+    },
+    // measured in celsius, shown in the unit picked in the settings
+    Temperature,
+}
+
+impl Format {
+    pub(crate) const PERCENT: Self = Self::Scaled {
+        scale: 1_f32,
+        decimals: 0,
+        unit: "%",
+    };
+
+    pub(crate) const fn scaled(scale: f32, decimals: usize, unit: &'static str) -> Self {
+        Self::Scaled {
+            scale,
+            decimals,
+            unit,
+        }
+    }
+}
+
+// a labelled metric in a statistics panel
+#[derive(Debug, Clone)]
+struct Metric {
+    label: &'static str,
+    format: Format,
+    statistics: RollingStatistics,
+}
+
+// the statistics shown in the panel at the bottom of a page
+#[derive(Debug, Clone)]
+pub(crate) struct StatisticsSet {
+    metrics: Vec<Metric>,
+}
+
+impl StatisticsSet {
+    // the metrics in the order they are shown
+    pub(crate) fn new(metrics: &[(&'static str, Format)]) -> Self {
+        Self {
+            metrics: metrics
+                .iter()
+                .map(|&(label, format)| Metric {
+                    label,
+                    format,
+                    statistics: RollingStatistics::default(),
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, label: &str, value: f32, timestamp: DateTime<Local>) {
+        if let Some(metric) = self.metrics.iter_mut().find(|metric| metric.label == label) {
+            metric.statistics.push(value, timestamp);
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        for metric in &mut self.metrics {
+            metric.statistics.reset();
+        }
+    }
+
+    // a table of the statistics, metrics without samples are left out
+    // the reset is disabled when not resettable, the minimum and maximum of another machine cannot be reset from here
+    pub(crate) fn view(
+        &self,
+        window: StatisticsWindow,
+        route: Route,
+        celsius: bool,
+        resettable: bool,
+    ) -> Element<Message> {
+        let cell = |value: String| text(value).size(14).width(Length::Fixed(COLUMN_WIDTH));
+
+        let header = ["", "Minimum", "Average", "Maximum", "Std Dev", "P95"]
+            .into_iter()
+            .fold(Row::new(), |row, label| row.push(cell(label.to_string())));
+
+        let rows = self.metrics.iter().filter_map(|metric| {
+            let statistics = metric.statistics.statistics(window)?;
+
+            let (statistics, decimals, unit) = match metric.format {
+                Format::Scaled {
+                    scale,
+                    decimals,
+                    unit,
+                } => (statistics.converted(scale, 0_f32), decimals, unit),
+                Format::Temperature if celsius => (statistics, 0, "°C"),
+                Format::Temperature => (statistics.converted(1.8, 32_f32), 0, "°F"),
+            };
+
+            let format = |value: f32| format!("{:.*}{}", decimals, value, unit);
+
+            Some(Element::from(row!(
+                cell(metric.label.to_string()),
+                cell(format(statistics.minimum)),
+                cell(format(statistics.average)),
+                cell(format(statistics.maximum)),
+                // the deviation gets an extra decimal since it is usually small
+                cell(format!("{:.*}{}", decimals + 1, statistics.deviation, unit)),
+                cell(format(statistics.p95)),
+            )))
+        });
+
+        // also resets the minimum and maximum kept by the hardware monitor
+        let mut reset = button(text("Reset Statistics").size(14))
+            .style(theme::Button::Custom(Box::new(SettingsButton)))
+            .padding(5);
+
+        if resettable {
+            reset = reset.on_press(Message::StatisticsReset(route));
+        }
+
+        container(
+            column!(
+                row!(
+                    text("Statistics").size(16),
+                    horizontal_space(Length::Fill),
+                    pick_list(
+                        &StatisticsWindow::ALL[..],
+                        Some(window),
+                        Message::StatisticsWindowChanged
+                    )
+                    .text_size(14)
+                    .padding(5)
+                    .style(theme::PickList::Custom(
+                        Rc::new(PickListStyle),
+                        Rc::new(PickListStyle),
+                    )),
+                    horizontal_space(Length::Fixed(10_f32)),
+                    reset,
+                )
+                .align_items(Alignment::Center),
+                header,
+                column(rows.collect()).spacing(2),
+            )
+            .spacing(5),
+        )
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the values 1 to 100, one a second
+    fn samples() -> RollingStatistics {
+        let start = Local::now();
+        let mut statistics = RollingStatistics::default();
+
+        for value in 1..=100 {
+            statistics.push(value as f32, start + Duration::seconds(value));
+        }
+
+        statistics
+    }
+
+    #[test]
+    fn window_p95_is_the_nearest_rank() {
+        let statistics = samples().statistics(StatisticsWindow::TenMinutes).unwrap();
+
+        assert_eq!(statistics.minimum, 1_f32);
+        assert_eq!(statistics.maximum, 100_f32);
+        assert_eq!(statistics.average, 50.5);
+        assert_eq!(statistics.p95, 95_f32);
+    }
+
+    #[test]
+    fn window_only_counts_recent_samples() {
+        // the last minute holds 41 to 100
+        let statistics = samples().statistics(StatisticsWindow::OneMinute).unwrap();
+
+        assert_eq!(statistics.minimum, 41_f32);
+        assert_eq!(statistics.p95, 97_f32);
+    }
+
+    #[test]
+    fn session_p95_is_within_the_sketch_error() {
+        let statistics = samples().statistics(StatisticsWindow::Session).unwrap();
+
+        assert_eq!(statistics.minimum, 1_f32);
+        assert_eq!(statistics.maximum, 100_f32);
+        assert!((statistics.average - 50.5).abs() < 1e-3);
+        assert!((statistics.p95 - 95_f32).abs() <= 95_f32 * 0.02);
+    }
+
+    #[test]
+    fn sketch_keeps_the_order_of_negative_values() {
+        assert!(sketch_index(-10_f64) < sketch_index(-1_f64));
+        assert!(sketch_index(-1_f64) < sketch_index(0_f64));
+        assert!(sketch_index(0_f64) < sketch_index(1_f64));
+        assert!((sketch_value(sketch_index(-50_f64)) + 50_f64).abs() <= 50_f64 * 0.02);
+    }
+
+    #[test]
+    fn reset_forgets_every_sample() {
+        let mut statistics = samples();
+        statistics.reset();
+
+        assert!(statistics.statistics(StatisticsWindow::Session).is_none());
+        assert!(statistics.statistics(StatisticsWindow::OneMinute).is_none());
+    }
+}
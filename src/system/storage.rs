@@ -1,13 +1,17 @@
 use std::rc::Rc;
 
+use chrono::{DateTime, Local};
+use iced::alignment::Vertical;
 use iced::widget::{
     button, column, container, horizontal_space, pick_list, row, text, vertical_space,
 };
-use iced::alignment::Vertical;
 use iced::{theme, Alignment, Element, Length};
 
-use crate::system::{Data, Hardware};
-use crate::ui::chart::LineGraph;
+use crate::alert::Severity;
+use crate::system::statistics::{Format, StatisticsSet, StatisticsWindow};
+use crate::system::{Data, Hardware, Sensor, SensorType};
+use crate::ui::chart::{overlay, overlay_scale, snapshot, LineGraph, Series, Snapshot, TimeWindow};
+use crate::ui::export::save_button;
 use crate::ui::style::button::ComponentSelect;
 use crate::ui::style::container::GraphBox;
 use crate::ui::style::pick_list::PickList as PickListStyle;
@@ -41,66 +45,93 @@ impl std::fmt::Display for GraphState {
 #[derive(Debug, Clone)]
 pub(crate) struct Storage {
     pub(crate) name: String,
-    index: usize,
+    // identifier assigned by OHM, used to reset its minimum and maximum values
+    pub(crate) identifier: String,
+    pub(crate) index: usize,
     read_rate: Data,
     read_graph: LineGraph,
     write_rate: Data,
     write_graph: LineGraph,
     temperature: Data,
-    temperature_graph: LineGraph,
+    pub(crate) temperature_graph: LineGraph,
     used_capacity: Data,
     activity: Data,
-    activity_graph: LineGraph,
+    pub(crate) activity_graph: LineGraph,
     data_read: Data,
     data_written: Data,
     pub(crate) graph_state: GraphState,
+    pub(crate) time_window: TimeWindow,
+    pub(crate) statistics: StatisticsSet,
 }
 
 impl Storage {
     pub(crate) fn new() -> Self {
         Self {
             name: String::new(),
+            identifier: String::new(),
             index: 0,
             read_rate: Data::default(),
             read_graph: LineGraph::new((119, 221, 119)),
             write_rate: Data::default(),
-            write_graph: LineGraph::new((119, 221, 119)),
+            write_graph: LineGraph::new((255, 190, 125)),
             temperature: Data::default(),
             temperature_graph: LineGraph::new((119, 221, 119)),
             used_capacity: Data::default(),
             activity: Data::default(),
-            activity_graph: LineGraph::new((119, 221, 119)),
+            activity_graph: LineGraph::new((119, 221, 119)).with_bounds(SensorType::Load.bounds()),
             data_read: Data::default(),
             data_written: Data::default(),
             graph_state: GraphState::Activity,
+            time_window: TimeWindow::OneMinute,
+            statistics: StatisticsSet::new(&[
+                ("Activity", Format::PERCENT),
+                ("Read Rate", Format::scaled(0.000_001, 1, " MB/s")),
+                ("Write Rate", Format::scaled(0.000_001, 1, " MB/s")),
+                ("Temperature", Format::Temperature),
+            ]),
         }
     }
 
-    pub(crate) fn update(&mut self, hardware_data: &Hardware, index: usize) {
+    pub(crate) fn update(
+        &mut self,
+        hardware_data: &Hardware,
+        index: usize,
+        timestamp: DateTime<Local>,
+    ) {
         self.name = hardware_data.name.clone();
+        self.identifier = hardware_data.identifier.clone();
         self.index = index;
 
         for sensor in &hardware_data.sensors {
-            let data = Data::from(sensor);
+            let data = Data::from(sensor, timestamp);
 
             match sensor.name.as_str() {
                 "Read Rate" => {
-                    self.read_graph.push_data(data.current);
+                    self.read_graph.push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Read Rate", data.current, data.timestamp);
                     self.read_rate = data;
                 }
                 "Write Rate" => {
-                    self.write_graph.push_data(data.current);
+                    self.write_graph.push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Write Rate", data.current, data.timestamp);
                     self.write_rate = data;
                 }
                 "Temperature" => {
-                    self.temperature_graph.push_data(data.current);
+                    self.temperature_graph
+                        .push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Temperature", data.current, data.timestamp);
                     self.temperature = data;
                 }
                 "Used Space" => {
                     self.used_capacity = data;
                 }
                 "Total Activity" => {
-                    self.activity_graph.push_data(data.current);
+                    self.activity_graph.push_data(data.current, data.timestamp);
+                    self.statistics
+                        .push("Activity", data.current, data.timestamp);
                     self.activity = data;
                 }
                 "Data Read" => {
@@ -114,13 +145,86 @@ impl Storage {
         }
     }
 
+    // read and write rates drawn on the same graph
+    fn rate_series(&self) -> Vec<Series> {
+        vec![
+            Series::new("Read", &self.read_graph, (119, 221, 119)),
+            Series::new("Write", &self.write_graph, (255, 190, 125)),
+        ]
+    }
+
+    // the two graphs on the page, for saving as an image
+    pub(crate) fn snapshots(&self) -> Vec<Snapshot> {
+        let (title, graph) = match self.graph_state {
+            GraphState::Activity => ("Activity (%)", &self.activity_graph),
+            GraphState::Temperature => ("Temperature (°C)", &self.temperature_graph),
+        };
+
+        vec![
+            snapshot(
+                title,
+                &[Series::new("", graph, (119, 221, 119))],
+                self.time_window,
+            ),
+            snapshot(
+                "Read / Write Rate (B/s)",
+                &self.rate_series(),
+                self.time_window,
+            ),
+        ]
+    }
+
+    // mark a reading on the graph showing it, if there is one
+    pub(crate) fn mark(&mut self, sensor: &Sensor, timestamp: DateTime<Local>) {
+        let graph = match sensor.name.as_str() {
+            "Read Rate" => &mut self.read_graph,
+            "Write Rate" => &mut self.write_graph,
+            "Temperature" => &mut self.temperature_graph,
+            "Total Activity" => &mut self.activity_graph,
+            _ => return,
+        };
+
+        graph.mark(sensor.value, timestamp);
+    }
+
+    pub(crate) fn clear_markers(&mut self) {
+        self.read_graph.clear_markers();
+        self.write_graph.clear_markers();
+        self.temperature_graph.clear_markers();
+        self.activity_graph.clear_markers();
+    }
+
+    // the activity, transfer rate and temperature shown under the name in the sidebar
+    pub(crate) fn summary(&self, celsius: bool) -> String {
+        if celsius {
+            format!(
+                "{:.0}% {:.0} MB/s ({:.0}°C)",
+                self.activity.current,
+                (self.read_rate.current + self.write_rate.current) / 1_000_000_f32,
+                self.temperature.current
+            )
+        } else {
+            format!(
+                "{:.0}% {:.0} MB/s ({:.0}°F)",
+                self.activity.current,
+                (self.read_rate.current + self.write_rate.current) / 1_000_000_f32,
+                self.temperature.current * 1.8 + 32_f32
+            )
+        }
+    }
+
+    // bytes read and written per second
+    pub(crate) fn throughput(&self) -> f32 {
+        self.read_rate.current + self.write_rate.current
+    }
+
     // small view of the widget located in the sidebar
-    pub fn view_small(&self, celsius: bool) -> Element<Message> {
+    pub fn view_small(&self, celsius: bool, alert: Option<Severity>) -> Element<Message> {
         // the entire widget is a button
         button(
             row!(
                 horizontal_space(Length::Fixed(5_f32)),
-                container(self.activity_graph.view()) // it contains the gpu load graph
+                container(self.activity_graph.thumbnail()) // it contains the gpu load graph
                     .style(theme::Container::Custom(Box::new(GraphBox::new((
                         119, 221, 119
                     )))))
@@ -131,42 +235,44 @@ impl Storage {
                     text(format!("Disk {}", self.index)),
                     text(&self.name).size(14),
                     // this is the text on the right side of the graph with stats summary
-                    text(if celsius {
-                        format!(
-                            "{:.0}% {:.0} MB/s ({:.0}°C)",
-                            self.activity.current,
-                            (self.read_rate.current + self.write_rate.current) / 1_000_000_f32,
-                            self.temperature.current
-                        )
-                    } else {
-                        format!(
-                            "{:.0}% {:.0} MB/s ({:.0}°F)",
-                            self.activity.current,
-                            (self.read_rate.current + self.write_rate.current) / 1_000_000_f32,
-                            self.temperature.current * 1.8 + 32_f32
-                        )
-                    })
-                    .size(14)
+                    text(self.summary(celsius)).size(14)
                 )
                 .spacing(2)
             )
             .align_items(Alignment::Center),
         )
         .on_press(Message::Navigate(Route::Storage(self.index))) // opens the gpu page when pressed
-        .style(theme::Button::Custom(Box::new(ComponentSelect)))
+        .style(theme::Button::Custom(Box::new(ComponentSelect { alert })))
         .width(Length::Fill)
         .height(Length::Fixed(75_f32))
         .into()
     }
 
     // large view of the widget, the storage page
-    pub(crate) fn view_large(&self, celsius: bool) -> Element<Message> {
+    pub(crate) fn view_large(
+        &self,
+        celsius: bool,
+        statistics_window: StatisticsWindow,
+        resettable: bool,
+    ) -> Element<Message> {
         column!(
             // the title bar
             row!(
                 text(format!("Disk {}", self.index))
                     .size(28)
                     .vertical_alignment(Vertical::Center),
+                horizontal_space(Length::Fixed(20_f32)),
+                // picklist for the time window
+                pick_list(&TimeWindow::ALL[..], Some(self.time_window), |window| {
+                    Message::TimeWindowChanged(Route::Storage(self.index), window)
+                })
+                .style(theme::PickList::Custom(
+                    Rc::new(PickListStyle),
+                    Rc::new(PickListStyle),
+                ))
+                .padding(5),
+                horizontal_space(Length::Fixed(10_f32)),
+                save_button(Route::Storage(self.index)),
                 horizontal_space(Length::Fill),
                 text(&self.name),
             )
@@ -178,19 +284,19 @@ impl Storage {
                 row!(
                     match self.graph_state {
                         GraphState::Activity => text(format!(
-                            "Activity (0-{:.2}%)",
-                            self.activity_graph.maximum_value
+                            "Activity (0-{}%)",
+                            self.activity_graph.scale(self.time_window).end
                         ))
                         .size(14),
                         GraphState::Temperature => text(if celsius {
                             format!(
                                 "Temperature (0-{:.0}°C)",
-                                self.temperature_graph.maximum_value
+                                self.temperature_graph.scale(self.time_window).end
                             )
                         } else {
                             format!(
                                 "Temperature (0-{:.0}°F)",
-                                self.temperature_graph.maximum_value as f32 * 1.8 + 32_f32
+                                self.temperature_graph.scale(self.time_window).end * 1.8 + 32_f32
                             )
                         })
                         .size(14),
@@ -213,8 +319,8 @@ impl Storage {
                 container(
                     // the actual graph
                     match self.graph_state {
-                        GraphState::Activity => self.activity_graph.view(),
-                        GraphState::Temperature => self.temperature_graph.view(),
+                        GraphState::Activity => self.activity_graph.view(self.time_window),
+                        GraphState::Temperature => self.temperature_graph.view(self.time_window),
                     },
                 )
                 .width(Length::Fill)
@@ -227,41 +333,22 @@ impl Storage {
             .width(Length::Fill)
             .height(Length::FillPortion(1)),
             vertical_space(Length::Fixed(20_f32)),
-            // the stats summary
-            row!(
-                column!(
-                    text(format!(
-                        "Read Rate (0-{:.2} MB/s)",
-                        self.read_graph.maximum_value / 1_000_000
-                    ))
-                    .size(14),
-                    container(self.read_graph.view())
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .style(theme::Container::Custom(Box::new(GraphBox::new((
-                            119, 221, 119,
-                        ))))),
-                )
-                .spacing(5)
-                .width(Length::Fill),
-                horizontal_space(Length::Fixed(20_f32)),
-                column!(
-                    text(format!(
-                        "Write Rate (0-{:.2} MB/s)",
-                        self.write_graph.maximum_value / 1_000_000
-                    ))
-                    .size(14),
-                    container(self.write_graph.view())
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .style(theme::Container::Custom(Box::new(GraphBox::new((
-                            119, 221, 119,
-                        ))))),
-                )
-                .spacing(5)
-                .width(Length::Fill)
-                .height(Length::FillPortion(1))
+            // read and write rates on one graph
+            column!(
+                text(format!(
+                    "Read / Write Rate (0-{} MB/s)",
+                    overlay_scale(&self.rate_series(), self.time_window).end / 1_000_000_f32
+                ))
+                .size(14),
+                container(overlay(self.rate_series(), self.time_window))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(theme::Container::Custom(Box::new(GraphBox::new((
+                        119, 221, 119,
+                    ))))),
             )
+            .spacing(5)
+            .width(Length::Fill)
             .height(Length::FillPortion(1)),
             vertical_space(Length::Fixed(20_f32)),
             row!(
@@ -307,7 +394,15 @@ impl Storage {
                     .size(24),
                 ),
             )
-            .spacing(20)
+            .spacing(20),
+            vertical_space(Length::Fixed(20_f32)),
+            // rolling statistics of the activity, rates and temperature
+            self.statistics.view(
+                statistics_window,
+                Route::Storage(self.index),
+                celsius,
+                resettable
+            ),
         )
         .padding(20)
         .into()
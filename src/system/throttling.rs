@@ -0,0 +1,425 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Local};
+use iced::widget::{column, container, horizontal_space, row, text, Row, Space};
+use iced::{theme, Element, Length};
+
+use crate::alert::Severity;
+use crate::system::distribution::format_duration;
+use crate::system::{Sensor, SensorType};
+use crate::ui::chart::TimeWindow;
+use crate::ui::style::container::{GraphBox, Segment};
+use crate::ui::Message;
+
+// clocks this far below their highest reading have dropped
+const CLOCK_DROP: f32 = 0.1;
+
+// a temperature within this many degrees of its limit is near it
+const THERMAL_HEADROOM: f32 = 5_f32;
+
+// power draw within this fraction of the limit is at it
+const POWER_MARGIN: f32 = 0.95;
+
+// an episode only starts once the clocks were held back for the same cause in this many readings in a row,
+// so a single slow reading while something is at its limit is not an episode
+const SUSTAINED_SAMPLES: usize = 3;
+
+// an episode only ends once the clocks have recovered for this long, so short recoveries do not split it
+const EPISODE_GAP_SECONDS: i64 = 3;
+
+// the number of finished episodes kept
+const EPISODE_HISTORY: usize = 100;
+
+// the number of episodes listed under the timeline
+const EPISODES_LISTED: usize = 3;
+
+// the timeline is divided into this many parts, episodes always get at least one
+const TIMELINE_PORTIONS: i64 = 1000;
+
+// what held the clocks back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cause {
+    Thermal,
+    Power,
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Thermal => "Thermal",
+                Self::Power => "Power limit",
+            }
+        )
+    }
+}
+
+// the power limit as reported by the hardware, some report the limit itself and some the draw against it
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PowerLimit {
+    Watts(f32),
+    Percent(f32),
+}
+
+impl PowerLimit {
+    // none if the sensor holds neither, or the limit is not set
+    pub(crate) fn from(sensor: &Sensor) -> Option<Self> {
+        match sensor.sensor_type {
+            SensorType::Power if sensor.value > 0_f32 => Some(Self::Watts(sensor.value)),
+            SensorType::Load | SensorType::Control => Some(Self::Percent(sensor.value)),
+            _ => None,
+        }
+    }
+
+    // power draw as a fraction of the limit
+    pub(crate) fn ratio(&self, draw: Option<f32>) -> Option<f32> {
+        match self {
+            Self::Watts(limit) => draw.map(|draw| draw / limit),
+            Self::Percent(percent) => Some(percent / 100_f32),
+        }
+    }
+}
+
+// the readings a throttling decision is made from
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Sample {
+    pub(crate) clock: f32,
+    // the highest clock the hardware monitor has seen
+    pub(crate) maximum_clock: f32,
+    // degrees left before the temperature limit
+    pub(crate) temperature_headroom: f32,
+    // power draw as a fraction of the limit, none if the hardware does not report a limit
+    pub(crate) power_ratio: Option<f32>,
+}
+
+impl Sample {
+    // the cause and how far the clocks dropped, if the sample is throttled
+    pub(crate) fn assess(&self) -> Option<(Cause, f32)> {
+        if self.maximum_clock <= 0_f32 {
+            return None;
+        }
+
+        let drop = 1_f32 - self.clock / self.maximum_clock;
+
+        if drop < CLOCK_DROP {
+            return None;
+        }
+
+        // clocks also drop when idle, so they only count while something is at its limit
+        if self.temperature_headroom <= THERMAL_HEADROOM {
+            Some((Cause::Thermal, drop))
+        } else if self.power_ratio.is_some_and(|ratio| ratio >= POWER_MARGIN) {
+            Some((Cause::Power, drop))
+        } else {
+            None
+        }
+    }
+}
+
+// a period where the clocks were held back
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Episode {
+    pub(crate) start: DateTime<Local>,
+    // the last time the clocks were held back
+    pub(crate) end: DateTime<Local>,
+    pub(crate) cause: Cause,
+    pub(crate) severity: Severity,
+    // the furthest the clocks dropped, as a fraction of the highest clock
+    pub(crate) drop: f32,
+}
+
+impl Episode {
+    fn new(cause: Cause, drop: f32, timestamp: DateTime<Local>) -> Self {
+        Self {
+            start: timestamp,
+            end: timestamp,
+            cause,
+            severity: severity(drop),
+            drop,
+        }
+    }
+
+    fn extend(&mut self, drop: f32, timestamp: DateTime<Local>) {
+        self.end = timestamp;
+        self.drop = self.drop.max(drop);
+        self.severity = severity(self.drop);
+    }
+}
+
+// the deeper the drop the more severe the episode
+fn severity(drop: f32) -> Severity {
+    if drop >= 0.4 {
+        Severity::Critical
+    } else if drop >= 0.2 {
+        Severity::Warning
+    } else {
+        Severity::Info
+    }
+}
+
+// detects throttling episodes and keeps a log of them
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Throttling {
+    // finished episodes, oldest first
+    episodes: VecDeque<Episode>,
+    current: Option<Episode>,
+    // throttling not yet seen for long enough to be an episode, with the number of readings it was seen in
+    candidate: Option<(Episode, usize)>,
+    // when the last sample was taken
+    latest: Option<DateTime<Local>>,
+}
+
+impl Throttling {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // the throttling seen in the latest readings, from Sample::assess
+    pub(crate) fn push(&mut self, throttled: Option<(Cause, f32)>, timestamp: DateTime<Local>) {
+        self.latest = Some(timestamp);
+
+        match (throttled, self.current.as_mut()) {
+            // the episode goes on
+            (Some((cause, drop)), Some(episode)) if episode.cause == cause => {
+                episode.extend(drop, timestamp);
+                self.candidate = None;
+            }
+            // a new episode once it has lasted, ending the previous one if the cause changed
+            (Some((cause, drop)), _) => {
+                let (episode, samples) = match self.candidate.take() {
+                    Some((mut episode, samples)) if episode.cause == cause => {
+                        episode.extend(drop, timestamp);
+                        (episode, samples + 1)
+                    }
+                    _ => (Episode::new(cause, drop, timestamp), 1),
+                };
+
+                if samples >= SUSTAINED_SAMPLES {
+                    self.finish();
+                    self.current = Some(episode);
+                } else {
+                    self.candidate = Some((episode, samples));
+                }
+            }
+            (None, Some(episode)) => {
+                self.candidate = None;
+
+                if timestamp - episode.end >= Duration::seconds(EPISODE_GAP_SECONDS) {
+                    self.finish();
+                }
+            }
+            (None, None) => self.candidate = None,
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(episode) = self.current.take() {
+            self.episodes.push_back(episode);
+
+            if self.episodes.len() > EPISODE_HISTORY {
+                self.episodes.pop_front();
+            }
+        }
+    }
+
+    // every episode, oldest first, including the one in progress
+    fn all(&self) -> impl DoubleEndedIterator<Item = &Episode> {
+        self.episodes.iter().chain(self.current.iter())
+    }
+
+    // a bar across the time window with the episodes colored by severity, and the latest episodes
+    pub(crate) fn view(&self, time_window: TimeWindow) -> Element<Message> {
+        let status = match &self.current {
+            Some(episode) => format!("{} throttling now", episode.cause),
+            None if self.episodes.is_empty() => String::from("No throttling this session"),
+            None => format!("{} episodes this session", self.episodes.len()),
+        };
+
+        let mut timeline = Row::new().height(Length::Fill);
+
+        if let Some(now) = self.latest {
+            let window_start = now - Duration::seconds(time_window.seconds());
+            let portions = |from: DateTime<Local>, to: DateTime<Local>| {
+                ((to - from).num_seconds() * TIMELINE_PORTIONS / time_window.seconds()) as u16
+            };
+
+            // where the last episode drawn ended
+            let mut cursor = window_start;
+
+            for episode in self.all().filter(|episode| episode.end > window_start) {
+                let start = episode.start.max(window_start);
+
+                timeline = timeline
+                    .push(horizontal_space(Length::FillPortion(portions(
+                        cursor, start,
+                    ))))
+                    .push(
+                        container(Space::new(Length::Fill, Length::Fill))
+                            .width(Length::FillPortion(portions(start, episode.end).max(1)))
+                            .height(Length::Fill)
+                            .style(theme::Container::Custom(Box::new(Segment::new(
+                                episode.severity,
+                            )))),
+                    );
+
+                cursor = episode.end;
+            }
+
+            timeline = timeline.push(horizontal_space(Length::FillPortion(portions(cursor, now))));
+        }
+
+        let episodes = self.all().rev().take(EPISODES_LISTED).map(|episode| {
+            Element::from(
+                text(format!(
+                    "{} - {}  ({})  {}, {}, clocks down {:.0}%",
+                    episode.start.format("%H:%M:%S"),
+                    episode.end.format("%H:%M:%S"),
+                    format_duration((episode.end - episode.start).num_seconds() as f32),
+                    episode.cause,
+                    episode.severity,
+                    episode.drop * 100_f32
+                ))
+                .size(14),
+            )
+        });
+
+        column!(
+            row!(
+                text("Throttling").size(16),
+                horizontal_space(Length::Fill),
+                text(status).size(14),
+            ),
+            container(timeline)
+                .padding(2)
+                .width(Length::Fill)
+                .height(Length::Fixed(16_f32))
+                .style(theme::Container::Custom(Box::new(GraphBox::new((
+                    235, 64, 52
+                ))))),
+            column(episodes.collect()).spacing(2),
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(throttling: &mut Throttling, start: DateTime<Local>, readings: &[Option<Cause>]) {
+        for (second, cause) in readings.iter().enumerate() {
+            throttling.push(
+                cause.map(|cause| (cause, 0.3)),
+                start + Duration::seconds(second as i64),
+            );
+        }
+    }
+
+    #[test]
+    fn sustained() {
+        let start = Local::now();
+        let mut throttling = Throttling::new();
+
+        throttle(
+            &mut throttling,
+            start,
+            &[Some(Cause::Thermal), Some(Cause::Thermal)],
+        );
+        assert!(throttling.current.is_none());
+
+        throttle(
+            &mut throttling,
+            start + Duration::seconds(2),
+            &[Some(Cause::Thermal)],
+        );
+        let episode = throttling.current.unwrap();
+        assert_eq!(episode.start, start);
+        assert_eq!(episode.end, start + Duration::seconds(2));
+        assert_eq!(episode.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn short_or_mixed_readings_are_not_episodes() {
+        let mut throttling = Throttling::new();
+
+        throttle(
+            &mut throttling,
+            Local::now(),
+            &[
+                Some(Cause::Thermal),
+                Some(Cause::Thermal),
+                None,
+                Some(Cause::Power),
+                Some(Cause::Thermal),
+                Some(Cause::Power),
+                Some(Cause::Power),
+                None,
+            ],
+        );
+
+        assert!(throttling.current.is_none());
+        assert!(throttling.episodes.is_empty());
+    }
+
+    #[test]
+    fn cause_change() {
+        let start = Local::now();
+        let mut throttling = Throttling::new();
+
+        throttle(
+            &mut throttling,
+            start,
+            &[
+                Some(Cause::Thermal),
+                Some(Cause::Thermal),
+                Some(Cause::Thermal),
+                Some(Cause::Power),
+                Some(Cause::Power),
+            ],
+        );
+        // the thermal episode goes on until the power limit has lasted
+        assert_eq!(throttling.current.unwrap().cause, Cause::Thermal);
+
+        throttle(
+            &mut throttling,
+            start + Duration::seconds(5),
+            &[Some(Cause::Power)],
+        );
+        assert_eq!(throttling.episodes.len(), 1);
+        assert_eq!(throttling.current.unwrap().cause, Cause::Power);
+        assert_eq!(
+            throttling.current.unwrap().start,
+            start + Duration::seconds(3)
+        );
+    }
+
+    #[test]
+    fn assess() {
+        let sample = Sample {
+            clock: 3000_f32,
+            maximum_clock: 5000_f32,
+            temperature_headroom: 2_f32,
+            power_ratio: None,
+        };
+        assert_eq!(
+            sample.assess().map(|(cause, _)| cause),
+            Some(Cause::Thermal)
+        );
+
+        let idle = Sample {
+            temperature_headroom: 40_f32,
+            ..sample
+        };
+        assert!(idle.assess().is_none());
+
+        let limited = Sample {
+            power_ratio: Some(0.99),
+            ..idle
+        };
+        assert_eq!(limited.assess().map(|(cause, _)| cause), Some(Cause::Power));
+    }
+}
@@ -1,20 +1,38 @@
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
 
+use iced::subscription::events_with;
 use iced::time::every;
 use iced::widget::scrollable::{Direction, Properties};
 use iced::widget::{
-    column, Button, Column, Container, PickList, Row, Scrollable, Space, Text, Toggler,
+    column, Button, Column, Container, PickList, Row, Scrollable, Space, Text, TextInput, Toggler,
 };
-use iced::{executor, theme, Alignment, Command, Length, Padding, Subscription};
+use iced::{executor, theme, window, Alignment, Command, Event, Length, Padding, Subscription};
 use iced::{Application, Element, Theme as IcedTheme};
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 
+use crate::alert::action;
+use crate::alert::{AlertEvent, Alerts};
 use crate::config::Config;
+use crate::fleet;
 use crate::gpu::GraphState;
-use crate::system::{HardwareMonitor, SystemStats};
+use crate::mqtt::{self, Publisher};
+use crate::recorder::Recorder;
+use crate::remote::{self, Event as RemoteEvent, RemoteConfig};
+use crate::server::{self, Server};
+use crate::sink::{self, Kind, Sink};
+use crate::system::anomaly::Anomalies;
+use crate::system::compare::Compare;
+use crate::system::correlation::Correlation;
+use crate::system::distribution::Distribution;
+use crate::system::fleet::Fleet;
+use crate::system::history::{History, SharedHistory};
+use crate::system::{HardwareMonitor, Report, SystemStats};
+use crate::ui::chart::Snapshot;
+use crate::ui::export::{self, ImageFormat, ImageSize};
 use crate::ui::style::button::SettingsButton;
 use crate::ui::style::container::{MainBox, SecondaryBox};
 use crate::ui::style::pick_list::PickList as PickListStyle;
@@ -27,6 +45,53 @@ pub(crate) struct App {
     stats: SystemStats,
     monitor: Option<Arc<Mutex<HardwareMonitor>>>,
     config: Config,
+    // the compare page is not part of the stats, so its state lives here
+    compare: Compare,
+    correlation: Correlation,
+    distribution: Distribution,
+    anomalies: Anomalies,
+    // the history of every sensor on this machine, used by pages which let the user pick any sensor and the json api
+    history: SharedHistory,
+    // the history of the instance shown instead of this machine, the json api keeps serving this machine's
+    remote_history: History,
+    // the active recording, if any
+    recorder: Option<Recorder>,
+    // counts the recordings, so failed writes of a previous one can be told apart
+    recording: usize,
+    // status of the last recording shown in settings
+    recording_status: Option<String>,
+    // status of the last saved image shown in the sidebar
+    export_status: Option<String>,
+    // set once the window has been asked to close
+    closing: bool,
+    // statistics reset while an update was running, reset again when it completes
+    pending_resets: Vec<Route>,
+    // the state of the alert rules for this machine, used to highlight the sidebar and run the actions
+    alerts: Alerts,
+    // the state of the alert rules for the instance shown instead, only used to highlight the sidebar
+    remote_alerts: Alerts,
+    // the last alert action that failed, shown in settings
+    alert_status: Option<String>,
+    // the latest report, read by the local server
+    reports: watch::Sender<Option<Arc<Report>>>,
+    // the local server, if it is running
+    server: Option<Arc<Server>>,
+    // what the server is doing, shown in settings
+    server_status: Option<String>,
+    // the address being typed in settings, saved once submitted
+    server_address: String,
+    // publishes to the mqtt broker while enabled
+    mqtt: Option<Arc<Publisher>>,
+    // the outputs writing the sensors elsewhere, while enabled
+    sinks: HashMap<Kind, Arc<Sink>>,
+    // the connection to the instance shown instead of this machine, shown in settings
+    remote_status: Option<String>,
+    // the remote address being typed in settings, saved once submitted
+    remote_address: String,
+    // the latest readings of every host on the fleet page
+    fleet: Fleet,
+    // the fleet host shown instead of this machine, for this session only so the remote config is kept
+    fleet_host: Option<RemoteConfig>,
 }
 
 impl From<Config> for App {
@@ -35,6 +100,31 @@ impl From<Config> for App {
             route: Route::Cpu,
             stats: SystemStats::new(),
             monitor: None, // monitor is initialized asynchronously later
+            compare: Compare::new(),
+            correlation: Correlation::new(),
+            distribution: Distribution::new(),
+            anomalies: Anomalies::new(),
+            history: SharedHistory::new(),
+            remote_history: History::new(),
+            recorder: None,
+            recording: 0,
+            recording_status: None,
+            export_status: None,
+            closing: false,
+            pending_resets: Vec::new(),
+            alerts: Alerts::new(),
+            remote_alerts: Alerts::new(),
+            alert_status: None,
+            reports: watch::channel(None).0,
+            server: None,
+            server_status: None,
+            server_address: config.server.address.clone(),
+            mqtt: None,
+            sinks: HashMap::new(),
+            remote_status: None,
+            remote_address: config.remote.address.clone(),
+            fleet: Fleet::new(),
+            fleet_host: None,
             config,
         }
     }
@@ -47,54 +137,61 @@ impl Application for App {
     type Flags = Config;
 
     fn new(flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let mut app = App::from(flags);
+
         // creating the hardware monitor takes a second so its done asynchronously
-        (
-            App::from(flags),
+        let mut commands = vec![
             Command::perform(HardwareMonitor::new(), Message::MonitorCreated),
-        )
+            app.restart_server(),
+            app.restart_mqtt(),
+        ];
+
+        // every output that is enabled
+        commands.extend(Kind::ALL.into_iter().map(|kind| app.restart_sink(kind)));
+
+        (app, Command::batch(commands))
     }
 
     fn title(&self) -> String {
-        String::from("Corroded Monitor")
+        if let Some(remote) = self.remote() {
+            format!("Corroded Monitor - {}", remote.address)
+        } else {
+            String::from("Corroded Monitor")
+        }
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match message {
+            // this machine is read even while another instance is shown, for the server, exporters and recorder
             Message::Update => match self.monitor.clone() {
                 Some(monitor) => {
-                    Command::perform(self.stats.clone().update(monitor), Message::UpdateCompleted)
+                    Command::perform(HardwareMonitor::read(monitor), Message::UpdateCompleted)
                 }
                 None => Command::none(), // hardware monitor is not created yet
             },
-            Message::UpdateCompleted(boxed) => {
-                let (mut updated_stats, new_visibility) = *boxed;
-
-                // in case the graph states have changed since the update began
-                updated_stats.cpu.graph_state = self.stats.cpu.graph_state;
-
-                for (index, gpu) in updated_stats.gpus.iter_mut().enumerate() {
-                    if !self.stats.gpus.is_empty() {
-                        gpu.graph_state_1 = self.stats.gpus[index].graph_state_1;
-                        gpu.graph_state_2 = self.stats.gpus[index].graph_state_2;
-                        gpu.graph_state_3 = self.stats.gpus[index].graph_state_3;
-                    }
+            Message::UpdateCompleted(report) => {
+                // only shown while no other instance is, its history is kept for the json api either way
+                if self.remote().is_none() {
+                    self.show_report(&report);
+                } else {
+                    self.history.edit(|history| history.push(&report));
                 }
 
-                for (index, disk) in updated_stats.disks.iter_mut().enumerate() {
-                    if !self.stats.disks.is_empty() {
-                        disk.graph_state = self.stats.disks[index].graph_state;
-                    }
-                }
+                let events = self.alerts.evaluate(&self.config.alerts, &report);
+                let mut commands = self.alert_actions(events);
+
+                // the server always answers with the latest readings
+                self.reports.send_replace(Some(report.clone()));
 
-                self.stats = updated_stats;
+                if let Some(recorder) = &self.recorder {
+                    let recording = self.recording;
 
-                if !new_visibility.is_empty() {
-                    // merges maps without overwriting data in config
-                    merge_maps(&mut self.config.visibility, &new_visibility);
-                    self.config.save().expect("Failed to save config");
+                    commands.push(Command::perform(recorder.write(report), move |result| {
+                        Message::RecordingWritten(recording, result)
+                    }));
                 }
 
-                Command::none()
+                Command::batch(commands)
             }
             Message::MonitorCreated(monitor) => {
                 self.monitor = Some(monitor);
@@ -108,6 +205,10 @@ impl Application for App {
                 self.stats.cpu.graph_state = state;
                 Command::none()
             }
+            Message::CpuHeatmapChanged(state) => {
+                self.stats.cpu.heatmap_metric = state;
+                Command::none()
+            }
             Message::GpuPickChanged(state) => {
                 if GraphState::REGION_ONE.contains(&state) {
                     for gpu in &mut self.stats.gpus {
@@ -132,6 +233,85 @@ impl Application for App {
 
                 Command::none()
             }
+            Message::ComparePickChanged(state) => {
+                self.compare.graph_state = state;
+                Command::none()
+            }
+            Message::CorrelationXChanged(choice) => {
+                self.correlation.x = Some(choice);
+                Command::none()
+            }
+            Message::CorrelationYChanged(choice) => {
+                self.correlation.y = Some(choice);
+                Command::none()
+            }
+            Message::CorrelationColorToggled(color_by_time) => {
+                self.correlation.color_by_time = color_by_time;
+                Command::none()
+            }
+            Message::DistributionSensorChanged(choice) => {
+                self.distribution.sensor = Some(choice);
+                Command::none()
+            }
+            Message::DistributionThresholdChanged(threshold) => {
+                self.distribution.threshold = threshold;
+                Command::none()
+            }
+            Message::AnomalyDetectionToggled(enabled) => {
+                self.config.anomaly_detection = enabled;
+                self.config.save().expect("Failed to save config");
+                Command::none()
+            }
+            Message::AnomalySelected(identifier) => {
+                self.anomalies.selected = Some(identifier);
+                Command::none()
+            }
+            Message::AnomaliesCleared => {
+                self.edit_shown_history(|history, anomalies, stats| {
+                    anomalies.clear(history, stats)
+                });
+                Command::none()
+            }
+            Message::TimeWindowChanged(route, window) => {
+                match route {
+                    Route::Cpu => self.stats.cpu.time_window = window,
+                    Route::Gpu(index) => self.stats.gpus[index].time_window = window,
+                    Route::Ram => self.stats.ram.time_window = window,
+                    Route::Storage(index) => self.stats.disks[index].time_window = window,
+                    Route::Network(index) => {
+                        self.stats.network_adapters[index].time_window = window
+                    }
+                    Route::Compare => self.compare.time_window = window,
+                    Route::Correlation => self.correlation.time_window = window,
+                    Route::Distribution => self.distribution.time_window = window,
+                    Route::Anomalies => self.anomalies.time_window = window,
+                    Route::Fleet | Route::Settings => {}
+                }
+
+                Command::none()
+            }
+            Message::StatisticsWindowChanged(window) => {
+                self.config.statistics_window = window;
+                self.config.save().expect("Failed to save config");
+                Command::none()
+            }
+            // resetting would clear this machine's minimum and maximum, not the shown machine's
+            Message::StatisticsReset(_) if self.remote().is_some() => Command::none(),
+            Message::StatisticsReset(route) => match self.stats.reset_statistics(&route) {
+                Some(identifier) => {
+                    self.pending_resets.push(route);
+
+                    match self.monitor.clone() {
+                        Some(monitor) => Command::perform(
+                            HardwareMonitor::reset_min_max(monitor, identifier),
+                            Message::StatisticsResetCompleted,
+                        ),
+                        None => Command::none(),
+                    }
+                }
+                None => Command::none(),
+            },
+            Message::StatisticsResetCompleted(()) => Command::none(),
             Message::ThemeChanged(theme) => {
                 self.config.theme = theme;
                 self.config.save().expect("Failed to save config");
@@ -140,6 +320,7 @@ impl Application for App {
             Message::TemperatureUnitChanged => {
                 self.config.celsius = !self.config.celsius;
                 self.config.save().expect("Failed to save config");
+                self.fleet.set_celsius(self.config.celsius);
                 Command::none()
             }
             Message::VisibilityChanged((name, visible)) => {
@@ -147,37 +328,334 @@ impl Application for App {
                 self.config.save().expect("Failed to save config");
                 Command::none()
             }
+            Message::RecordingToggled => match self.recorder.take() {
+                Some(recorder) => Command::perform(recorder.finish(), Message::RecordingFinished),
+                None => {
+                    match Recorder::create() {
+                        Ok(recorder) => {
+                            self.recording_status =
+                                Some(format!("Recording to {}", recorder.path.display()));
+                            self.recorder = Some(recorder);
+                            self.recording += 1;
+                        }
+                        Err(error) => {
+                            self.recording_status =
+                                Some(format!("Failed to start recording: {}", error))
+                        }
+                    }
+
+                    Command::none()
+                }
+            },
+            Message::RecordingWritten(recording, result) => {
+                // a recording that was already stopped reports its errors when it finishes
+                let current = self.recorder.is_some() && recording == self.recording;
+
+                if let (Err(error), true) = (result, current) {
+                    // the file cannot be trusted after a failed write, stop recording
+                    self.recorder = None;
+                    self.recording_status = Some(format!("Recording stopped: {}", error));
+                }
+
+                Command::none()
+            }
+            Message::RecordingFinished(result) => {
+                self.recording_status = Some(match result {
+                    Ok(path) => format!("Saved recording to {}", path.display()),
+                    Err(error) => format!("Failed to save recording: {}", error),
+                });
+
+                if self.closing {
+                    window::close()
+                } else {
+                    Command::none()
+                }
+            }
+            Message::ExportGraph(mut snapshot) => {
+                // a single graph is titled by its legend, the page title goes above it
+                snapshot.title = snapshot.names().join(" / ");
+                self.export(self.page_title(&self.route), vec![snapshot])
+            }
+            Message::ExportPage(route) => {
+                let snapshots = match route {
+                    Route::Cpu => self.stats.cpu.snapshots(),
+                    Route::Gpu(index) => self.stats.gpus[index].snapshots(),
+                    Route::Ram => self.stats.ram.snapshots(),
+                    Route::Storage(index) => self.stats.disks[index].snapshots(),
+                    Route::Network(index) => self.stats.network_adapters[index].snapshots(),
+                    Route::Compare => self.compare.snapshots(&self.stats),
+                    Route::Correlation
+                    | Route::Distribution
+                    | Route::Anomalies
+                    | Route::Fleet
+                    | Route::Settings => Vec::new(),
+                };
+
+                if snapshots.is_empty() {
+                    Command::none()
+                } else {
+                    self.export(self.page_title(&route), snapshots)
+                }
+            }
+            Message::ExportFinished(result) => {
+                self.export_status = Some(match result {
+                    Ok(path) => format!("Saved image to {}", path.display()),
+                    Err(error) => format!("Failed to save image: {}", error),
+                });
+
+                Command::none()
+            }
+            Message::AlertActionFinished(result) => {
+                if let Err(error) = result {
+                    self.alert_status = Some(error);
+                }
+
+                Command::none()
+            }
+            Message::ServerStarted(result) => {
+                match result {
+                    // turned off again while it was starting
+                    Ok(_) if !self.config.server.enabled() => {}
+                    Ok(server) => {
+                        self.server_status = Some(format!("Serving on http://{}", server.address));
+                        self.server = Some(server);
+                    }
+                    Err(error) => self.server_status = Some(error),
+                }
+
+                Command::none()
+            }
+            Message::ServerMetricsToggled(enabled) => {
+                self.config.server.metrics = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_server()
+            }
+            Message::ServerApiToggled(enabled) => {
+                self.config.server.api = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_server()
+            }
+            Message::ServerStreamToggled(enabled) => {
+                self.config.server.stream = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_server()
+            }
+            Message::ServerDashboardToggled(enabled) => {
+                self.config.server.dashboard = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_server()
+            }
+            Message::ServerAddressChanged(address) => {
+                self.server_address = address;
+                Command::none()
+            }
+            Message::ServerAddressSubmitted => {
+                match self.server_address.trim().parse::<SocketAddr>() {
+                    Ok(address) => {
+                        self.config.server.address = address.to_string();
+                        self.config.save().expect("Failed to save config");
+                        self.restart_server()
+                    }
+                    Err(_) => {
+                        self.server_status = Some(format!(
+                            "{} is not an address, expected e.g. {}",
+                            self.server_address,
+                            server::DEFAULT_ADDRESS
+                        ));
+                        Command::none()
+                    }
+                }
+            }
+            Message::MqttStarted(publisher) => {
+                // turned off again while it was starting
+                if self.config.mqtt.enabled {
+                    self.mqtt = Some(publisher);
+                }
+
+                Command::none()
+            }
+            Message::MqttToggled(enabled) => {
+                self.config.mqtt.enabled = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_mqtt()
+            }
+            Message::MqttDiscoveryToggled(enabled) => {
+                self.config.mqtt.discovery = enabled;
+                self.config.save().expect("Failed to save config");
+                self.restart_mqtt()
+            }
+            Message::SinkStarted(sink) => {
+                // turned off again while it was starting
+                if self.config.sinks.enabled(sink.kind) {
+                    self.sinks.insert(sink.kind, sink);
+                }
+
+                Command::none()
+            }
+            Message::SinkToggled(kind, enabled) => {
+                self.config.sinks.set_enabled(kind, enabled);
+                self.config.save().expect("Failed to save config");
+                self.restart_sink(kind)
+            }
+            Message::Remote(event) => match event {
+                RemoteEvent::Connected => {
+                    self.remote_status = self
+                        .remote()
+                        .map(|remote| format!("Connected to {}", remote.address));
+                    Command::none()
+                }
+                // only shown, the exporters, recorder and alert actions are for this machine's readings,
+                // readings from before it was turned off may still be queued
+                RemoteEvent::Report(report) if self.remote().is_some() => {
+                    self.show_report(&report);
+                    self.remote_alerts.evaluate(&self.config.alerts, &report);
+                    Command::none()
+                }
+                RemoteEvent::Report(_) => Command::none(),
+                RemoteEvent::Disconnected(reason) => {
+                    self.remote_status = Some(format!("Disconnected: {}, reconnecting", reason));
+                    Command::none()
+                }
+            },
+            Message::RemoteToggled(enabled) => {
+                if enabled && self.config.remote.address.is_empty() {
+                    self.remote_status = Some(String::from(
+                        "Enter the address of the other instance's server first",
+                    ));
+                    return Command::none();
+                }
+
+                // turning it off also returns from a fleet host
+                self.fleet_host = None;
+                self.config.remote.enabled = enabled;
+                self.config.save().expect("Failed to save config");
+                self.clear_readings();
+                Command::none()
+            }
+            Message::RemoteAddressChanged(address) => {
+                self.remote_address = address;
+                Command::none()
+            }
+            Message::RemoteAddressSubmitted => {
+                let address = self.remote_address.trim().to_string();
+
+                // a host name or ip address with the port the server listens on
+                if !address
+                    .rsplit_once(':')
+                    .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+                {
+                    self.remote_status = Some(format!(
+                        "{} is not an address, expected e.g. 192.168.1.20:9184",
+                        address
+                    ));
+                    return Command::none();
+                }
+
+                self.config.remote.address = address;
+                self.config.save().expect("Failed to save config");
+
+                // the subscription reconnects to the new address, instead of a fleet host if one is shown
+                if self.config.remote.enabled {
+                    self.fleet_host = None;
+                    self.clear_readings();
+                }
+
+                Command::none()
+            }
+            Message::Fleet(event) => {
+                self.fleet
+                    .update(event, &self.config.fleet, self.config.celsius);
+                Command::none()
+            }
+            Message::FleetHostSelected(index) => {
+                let Some(host) = self.config.fleet.hosts.get(index).cloned() else {
+                    return Command::none();
+                };
+
+                // already showing it, only its pages need opening
+                if self.remote().is_some_and(|remote| {
+                    remote.address == host.address && remote.token == host.token
+                }) {
+                    self.route = Route::Cpu;
+                    return Command::none();
+                }
+
+                // the subscription connects to the host's stream instead
+                self.fleet_host = Some(RemoteConfig {
+                    enabled: true,
+                    address: host.address,
+                    token: host.token,
+                });
+                self.clear_readings();
+                Command::none()
+            }
+            Message::ExportFormatChanged(format) => {
+                self.config.export_format = format;
+                self.config.save().expect("Failed to save config");
+                Command::none()
+            }
+            Message::ExportSizeChanged(size) => {
+                self.config.export_size = size;
+                self.config.save().expect("Failed to save config");
+                Command::none()
+            }
+            Message::CloseRequested => {
+                self.closing = true;
+
+                // finish the recording before exiting so the file is readable
+                match self.recorder.take() {
+                    Some(recorder) => {
+                        Command::perform(recorder.finish(), Message::RecordingFinished)
+                    }
+                    None => window::close(),
+                }
+            }
         }
     }
 
     // the base of the GUI
     fn view(&self) -> Element<'_, Self::Message> {
+        // statistics can only be reset while this machine is shown, the reset goes to its hardware monitor
+        let local = self.remote().is_none();
+        // the alert states and sensor histories of the machine shown
+        let alerts = self.shown_alerts();
+        let history = self.shown_history();
+
         // build the side bar with the visible hardware
         let mut side_bar_items = Vec::new();
 
         if self.config.is_visible(&self.stats.cpu.name) {
-            side_bar_items.push(self.stats.cpu.view_small(self.config.celsius))
+            side_bar_items.push(self.stats.cpu.view_small(
+                self.config.celsius,
+                alerts.severity(&self.stats.cpu.identifier),
+            ))
         }
 
         for gpu in &self.stats.gpus {
             if self.config.is_visible(&gpu.name) {
-                side_bar_items.push(gpu.view_small(self.config.celsius))
+                side_bar_items
+                    .push(gpu.view_small(self.config.celsius, alerts.severity(&gpu.identifier)))
             }
         }
 
         if self.config.is_visible(&self.stats.ram.name) {
-            side_bar_items.push(self.stats.ram.view_small())
+            side_bar_items.push(
+                self.stats
+                    .ram
+                    .view_small(alerts.severity(&self.stats.ram.identifier)),
+            )
         }
 
         for disk in &self.stats.disks {
             if self.config.is_visible(&disk.name) {
-                side_bar_items.push(disk.view_small(self.config.celsius))
+                side_bar_items
+                    .push(disk.view_small(self.config.celsius, alerts.severity(&disk.identifier)))
             }
         }
 
         for adapter in &self.stats.network_adapters {
             if self.config.is_visible(&adapter.name) {
-                side_bar_items.push(adapter.view_small())
+                side_bar_items.push(adapter.view_small(alerts.severity(&adapter.identifier)))
             }
         }
 
@@ -211,34 +689,20 @@ impl Application for App {
                     Scrollable::new(
                         column(side_bar_items)
                             .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Compare", Route::Compare))
+                            .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Correlation", Route::Correlation))
+                            .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Distribution", Route::Distribution))
+                            .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Anomalies", Route::Anomalies))
+                            .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Fleet", Route::Fleet))
+                            .push(Space::new(Length::Fill, Length::Fixed(10.0)))
+                            .push(page_button("Settings", Route::Settings))
                             .push(
-                                Row::new()
-                                    .push(Space::new(Length::FillPortion(1), Length::Shrink))
-                                    .push(
-                                        Button::new(
-                                            Column::new()
-                                                .push(Space::new(Length::Fill, Length::Fill))
-                                                .push(
-                                                    Row::new()
-                                                        .push(Space::new(
-                                                            Length::Fill,
-                                                            Length::Shrink,
-                                                        ))
-                                                        .push(Text::new("Settings"))
-                                                        .push(Space::new(
-                                                            Length::Fill,
-                                                            Length::Shrink,
-                                                        )),
-                                                )
-                                                .push(Space::new(Length::Fill, Length::Fill)),
-                                        )
-                                        .on_press(Message::Navigate(Route::Settings))
-                                        .style(theme::Button::Custom(Box::new(SettingsButton)))
-                                        .width(Length::FillPortion(3))
-                                        .height(Length::Fixed(50.0))
-                                        .padding(Padding::new(10.0)),
-                                    )
-                                    .push(Space::new(Length::FillPortion(1), Length::Shrink)),
+                                Text::new(self.export_status.as_deref().unwrap_or_default())
+                                    .size(14),
                             )
                             .push(Space::new(Length::Fill, Length::Fixed(20.0))),
                     )
@@ -252,32 +716,68 @@ impl Application for App {
                 .width(Length::Fixed(300.0)),
             )
             .push(match self.route {
-                Route::Cpu => Container::new(self.stats.cpu.view_large(self.config.celsius))
-                    .style(theme::Container::Custom(Box::new(MainBox)))
-                    .height(Length::Fill)
-                    .width(Length::Fill),
-                Route::Gpu(index) => {
-                    Container::new(self.stats.gpus[index].view_large(self.config.celsius))
-                        .style(theme::Container::Custom(Box::new(MainBox)))
-                        .height(Length::Fill)
-                        .width(Length::Fill)
-                }
-                Route::Ram => Container::new(self.stats.ram.view_large())
-                    .style(theme::Container::Custom(Box::new(MainBox)))
-                    .height(Length::Fill)
-                    .width(Length::Fill),
-                Route::Storage(index) => {
-                    Container::new(self.stats.disks[index].view_large(self.config.celsius))
+                Route::Cpu => Container::new(
+                    self.stats
+                        .cpu
+                        .view_large(self.config.celsius, self.config.statistics_window, local),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+                Route::Gpu(index) => Container::new(
+                    self.stats.gpus[index]
+                        .view_large(self.config.celsius, self.config.statistics_window, local),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+                Route::Ram => {
+                    Container::new(self.stats.ram.view_large(self.config.statistics_window, local))
                         .style(theme::Container::Custom(Box::new(MainBox)))
                         .height(Length::Fill)
                         .width(Length::Fill)
                 }
-                Route::Network(index) => {
-                    Container::new(self.stats.network_adapters[index].view_large())
+                Route::Storage(index) => Container::new(
+                    self.stats.disks[index]
+                        .view_large(self.config.celsius, self.config.statistics_window, local),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+                Route::Network(index) => Container::new(
+                    self.stats.network_adapters[index]
+                        .view_large(self.config.statistics_window, local),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+                Route::Compare => {
+                    Container::new(self.compare.view_large(&self.stats, self.config.celsius))
                         .style(theme::Container::Custom(Box::new(MainBox)))
                         .height(Length::Fill)
                         .width(Length::Fill)
                 }
+                Route::Correlation => Container::new(self.correlation.view_large(history))
+                    .style(theme::Container::Custom(Box::new(MainBox)))
+                    .height(Length::Fill)
+                    .width(Length::Fill),
+                Route::Distribution => Container::new(self.distribution.view_large(history))
+                    .style(theme::Container::Custom(Box::new(MainBox)))
+                    .height(Length::Fill)
+                    .width(Length::Fill),
+                Route::Anomalies => Container::new(
+                    self.anomalies
+                        .view_large(history, self.config.anomaly_detection),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+                Route::Fleet => Container::new(
+                    self.fleet.view_large(&self.config.fleet),
+                )
+                .style(theme::Container::Custom(Box::new(MainBox)))
+                .height(Length::Fill)
+                .width(Length::Fill),
                 Route::Settings => Container::new(
                     Scrollable::new(
                         // entire settings page is scrollable
@@ -322,6 +822,299 @@ impl Application for App {
                                     )
                                     .push(Text::new("Celsius").size(20)),
                             )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before recording options
+                            .push(Text::new("Recording").size(28))
+                            .push(
+                                Row::new() // start or stop recording sensor data to parquet
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(
+                                        Button::new(Text::new(if self.recorder.is_some() {
+                                            "Stop Recording"
+                                        } else {
+                                            "Start Recording"
+                                        }))
+                                        .on_press(Message::RecordingToggled)
+                                        .style(theme::Button::Custom(Box::new(SettingsButton)))
+                                        .padding(Padding::new(10.0)),
+                                    )
+                                    .push(
+                                        Text::new(
+                                            self.recording_status.as_deref().unwrap_or_default(),
+                                        )
+                                        .size(16),
+                                    ),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before export options
+                            .push(Text::new("Images").size(28))
+                            .push(
+                                Row::new() // file type and resolution of saved graphs
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Format").size(20))
+                                    .push(
+                                        PickList::new(
+                                            &ImageFormat::ALL[..],
+                                            Some(self.config.export_format),
+                                            Message::ExportFormatChanged,
+                                        )
+                                        .style(theme::PickList::Custom(
+                                            Rc::new(PickListStyle),
+                                            Rc::new(PickListStyle),
+                                        ))
+                                        .padding(5),
+                                    )
+                                    .push(Text::new("Resolution").size(20))
+                                    .push(
+                                        PickList::new(
+                                            &ImageSize::ALL[..],
+                                            Some(self.config.export_size),
+                                            Message::ExportSizeChanged,
+                                        )
+                                        .style(theme::PickList::Custom(
+                                            Rc::new(PickListStyle),
+                                            Rc::new(PickListStyle),
+                                        ))
+                                        .padding(5),
+                                    ),
+                            )
+                            .push(
+                                Text::new(
+                                    "Save Image saves every graph on a page, right click a graph to save only that graph",
+                                )
+                                .size(16),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before alert options
+                            .push(Text::new("Alerts").size(28))
+                            .push(
+                                Text::new(format!(
+                                    "{} alert rules, edit them and their actions in config.json",
+                                    self.config.alerts.len()
+                                ))
+                                .size(16),
+                            )
+                            .push(
+                                Text::new(self.alert_status.as_deref().unwrap_or_default())
+                                    .size(16),
+                            )
+                            .push(
+                                Row::new() // flag readings outside the normal range of their sensor
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Anomaly detection").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.anomaly_detection,
+                                            Message::AnomalyDetectionToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before remote options
+                            .push(Text::new("Remote").size(28))
+                            .push(
+                                Row::new() // show another instance's sensors instead of this machine's
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Show another machine").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.remote().is_some(),
+                                            Message::RemoteToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    )
+                                    .push(
+                                        TextInput::new("192.168.1.20:9184", &self.remote_address)
+                                            .on_input(Message::RemoteAddressChanged)
+                                            .on_submit(Message::RemoteAddressSubmitted)
+                                            .size(16)
+                                            .width(Length::Fixed(200.0)),
+                                    )
+                                    .push(
+                                        Text::new(self.remote_status.as_deref().unwrap_or_default())
+                                            .size(16),
+                                    ),
+                            )
+                            .push(
+                                Text::new(
+                                    "The other machine needs its server with the stream turned on, set its token as \"token\" under \"remote\" in config.json",
+                                )
+                                .size(16),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before server options
+                            .push(Text::new("Server").size(28))
+                            .push(
+                                Row::new() // the address the local server listens on
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Address").size(20))
+                                    .push(
+                                        TextInput::new(server::DEFAULT_ADDRESS, &self.server_address)
+                                            .on_input(Message::ServerAddressChanged)
+                                            .on_submit(Message::ServerAddressSubmitted)
+                                            .size(16)
+                                            .width(Length::Fixed(200.0)),
+                                    )
+                                    .push(
+                                        Text::new(self.server_status.as_deref().unwrap_or_default())
+                                            .size(16),
+                                    ),
+                            )
+                            .push(
+                                Row::new() // sensors in the prometheus format at /metrics
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Prometheus metrics").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.server.metrics,
+                                            Message::ServerMetricsToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(
+                                Row::new() // readings and history as json at /hardware and /sensors
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("JSON API").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.server.api,
+                                            Message::ServerApiToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(
+                                Row::new() // every new reading over a websocket at /stream
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("WebSocket stream").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.server.stream,
+                                            Message::ServerStreamToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(
+                                Row::new() // a web page with the readings, for phones and other computers
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Web dashboard").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.server.dashboard,
+                                            Message::ServerDashboardToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(
+                                Text::new(if self.config.server.token.is_some() {
+                                    "Requests need the bearer token from config.json"
+                                } else {
+                                    "Set a token in config.json to require it as a bearer token"
+                                })
+                                .size(16),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before mqtt options
+                            .push(Text::new("MQTT").size(28))
+                            .push(
+                                Row::new() // publish every sensor to the broker from config.json
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Publish").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.mqtt.enabled,
+                                            Message::MqttToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    )
+                                    .push(
+                                        Text::new(match &self.mqtt {
+                                            Some(publisher) => publisher.status(),
+                                            None => format!(
+                                                "{}:{}, edit the broker in config.json",
+                                                self.config.mqtt.host, self.config.mqtt.port
+                                            ),
+                                        })
+                                        .size(16),
+                                    ),
+                            )
+                            .push(
+                                Row::new() // every sensor shows up in home assistant on its own
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Text::new("Home Assistant discovery").size(20))
+                                    .push(
+                                        Toggler::new(
+                                            None,
+                                            self.config.mqtt.discovery,
+                                            Message::MqttDiscoveryToggled,
+                                        )
+                                        .width(Length::Shrink)
+                                        .style(theme::Toggler::Custom(Box::new(TogglerStyle))),
+                                    ),
+                            )
+                            .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before output options
+                            .push(Text::new("Outputs").size(28))
+                            .push(
+                                column(
+                                    Kind::ALL
+                                        .into_iter()
+                                        .map(|kind| {
+                                            Row::new() // write every sensor to the destination from config.json
+                                                .spacing(10)
+                                                .align_items(Alignment::Center)
+                                                .push(Text::new(kind.to_string()).size(20))
+                                                .push(
+                                                    Toggler::new(
+                                                        None,
+                                                        self.config.sinks.enabled(kind),
+                                                        move |enabled| {
+                                                            Message::SinkToggled(kind, enabled)
+                                                        },
+                                                    )
+                                                    .width(Length::Shrink)
+                                                    .style(theme::Toggler::Custom(Box::new(
+                                                        TogglerStyle,
+                                                    ))),
+                                                )
+                                                .push(
+                                                    Text::new(match self.sinks.get(&kind) {
+                                                        Some(sink) => sink.status(),
+                                                        None => format!(
+                                                            "{}, edit it in config.json",
+                                                            self.config.sinks.destination(kind)
+                                                        ),
+                                                    })
+                                                    .size(16),
+                                                )
+                                                .into()
+                                        })
+                                        .collect(),
+                                )
+                                .spacing(10),
+                            )
                             .push(Space::new(Length::Shrink, Length::Fixed(10.0))) // extra space before visibility options
                             .push(Text::new("Visibility").size(28)) // visibility options title
                             .push(column(visibility_options).spacing(10)), // build a column of visibility options
@@ -350,10 +1143,245 @@ impl Application for App {
         }
     }
 
-    // update the stats every second
+    // update the stats every second, intercept the window closing and follow other instances
     fn subscription(&self) -> Subscription<Message> {
-        every(Duration::from_millis(1000)).map(|_| Message::Update)
+        let mut subscriptions = vec![
+            every(Duration::from_millis(1000)).map(|_| Message::Update),
+            events_with(|event, _status| match event {
+                Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+                _ => None,
+            }),
+        ];
+
+        if let Some(remote) = self.remote() {
+            subscriptions.push(remote::connect(remote.clone()).map(Message::Remote));
+        }
+
+        // the hosts are only polled while they are shown
+        if matches!(self.route, Route::Fleet) && !self.config.fleet.hosts.is_empty() {
+            subscriptions.push(fleet::poll(self.config.fleet.clone()).map(Message::Fleet));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+}
+
+impl App {
+    // the title of a page, used as the title of saved images
+    fn page_title(&self, route: &Route) -> String {
+        match route {
+            Route::Cpu => format!("CPU {}", self.stats.cpu.name),
+            Route::Gpu(index) => format!("GPU {} {}", index, self.stats.gpus[*index].name),
+            Route::Ram => String::from("RAM"),
+            Route::Storage(index) => format!("Disk {} {}", index, self.stats.disks[*index].name),
+            Route::Network(index) => format!(
+                "Network {} {}",
+                index, self.stats.network_adapters[*index].name
+            ),
+            Route::Compare => String::from("Compare"),
+            Route::Correlation => String::from("Correlation"),
+            Route::Distribution => String::from("Distribution"),
+            Route::Anomalies => String::from("Anomalies"),
+            Route::Fleet => String::from("Fleet"),
+            Route::Settings => String::from("Settings"),
+        }
+    }
+
+    // run the actions of the rules behind the alerts, each in its own task so slow ones do not hold up updates
+    fn alert_actions(&self, events: Vec<AlertEvent>) -> Vec<Command<Message>> {
+        let mut commands = Vec::new();
+
+        for event in events {
+            let Some(rule) = self.config.alerts.get(event.rule_index) else {
+                continue;
+            };
+
+            for action in &rule.actions {
+                commands.push(Command::perform(
+                    action::run(action.clone(), event.clone()),
+                    Message::AlertActionFinished,
+                ));
+            }
+        }
+
+        commands
+    }
+
+    // the instance shown instead of this machine, a fleet host picked this session or the one in the config
+    fn remote(&self) -> Option<&RemoteConfig> {
+        self.fleet_host
+            .as_ref()
+            .or(Some(&self.config.remote).filter(|remote| remote.enabled))
+    }
+
+    // update the pages from a report, read locally or from another instance
+    fn show_report(&mut self, report: &Report) {
+        let new_visibility = self.stats.apply(report);
+
+        // the report was read before these resets, so its samples are from before them
+        for route in self.pending_resets.drain(..) {
+            self.stats.reset_statistics(&route);
+        }
+
+        let anomaly_detection = self.config.anomaly_detection;
+
+        self.edit_shown_history(|history, anomalies, stats| {
+            history.push(report);
+
+            if anomaly_detection {
+                anomalies.evaluate(report, history, stats);
+            }
+        });
+
+        if !new_visibility.is_empty() {
+            // merges maps without overwriting data in config
+            merge_maps(&mut self.config.visibility, &new_visibility);
+            self.config.save().expect("Failed to save config");
+        }
+    }
+
+    // the history of the machine shown
+    fn shown_history(&self) -> &History {
+        match self.remote() {
+            Some(_) => &self.remote_history,
+            None => &self.history,
+        }
+    }
+
+    // the alert states of the machine shown
+    fn shown_alerts(&self) -> &Alerts {
+        match self.remote() {
+            Some(_) => &self.remote_alerts,
+            None => &self.alerts,
+        }
+    }
+
+    // change the history of the machine shown, this machine's is published to the json api
+    fn edit_shown_history<F>(&mut self, change: F)
+    where
+        F: FnOnce(&mut History, &mut Anomalies, &mut SystemStats),
+    {
+        let remote = self.remote().is_some();
+        let (anomalies, stats) = (&mut self.anomalies, &mut self.stats);
+
+        if remote {
+            change(&mut self.remote_history, anomalies, stats);
+        } else {
+            self.history
+                .edit(|history| change(history, anomalies, stats));
+        }
+    }
+
+    // forget the shown readings when switching between this machine and another instance,
+    // this machine's history and alerts are kept since they are read in every mode
+    fn clear_readings(&mut self) {
+        let (anomalies, stats) = (&mut self.anomalies, &mut self.stats);
+        self.history.edit(|history| anomalies.clear(history, stats));
+
+        self.stats = SystemStats::new();
+        self.remote_history = History::new();
+        self.compare = Compare::new();
+        self.correlation = Correlation::new();
+        self.distribution = Distribution::new();
+        self.anomalies = Anomalies::new();
+        self.remote_alerts = Alerts::new();
+        self.pending_resets.clear();
+        self.remote_status = None;
+
+        // the pages of the previous hardware may not exist anymore
+        self.route = Route::Cpu;
+    }
+
+    // stop the local server and start it again with the current config, if anything is enabled
+    fn restart_server(&mut self) -> Command<Message> {
+        // dropping the server stops it, freeing the address for the new one
+        self.server = None;
+
+        if !self.config.server.enabled() {
+            self.server_status = None;
+            return Command::none();
+        }
+
+        self.server_status = Some(format!("Starting on {}", self.config.server.address));
+
+        Command::perform(
+            server::start(
+                self.config.server.clone(),
+                self.reports.subscribe(),
+                self.history.subscribe(),
+            ),
+            Message::ServerStarted,
+        )
     }
+
+    // stop publishing and start again with the current config, if publishing is enabled
+    fn restart_mqtt(&mut self) -> Command<Message> {
+        // dropping the publisher disconnects it
+        self.mqtt = None;
+
+        if !self.config.mqtt.enabled {
+            return Command::none();
+        }
+
+        Command::perform(
+            mqtt::start(self.config.mqtt.clone(), self.reports.subscribe()),
+            Message::MqttStarted,
+        )
+    }
+
+    // stop the output and start it again with the current config, if it is enabled
+    fn restart_sink(&mut self, kind: Kind) -> Command<Message> {
+        // dropping the sink stops it, lines not yet written are lost
+        self.sinks.remove(&kind);
+
+        if !self.config.sinks.enabled(kind) {
+            return Command::none();
+        }
+
+        Command::perform(
+            sink::start(kind, self.config.sinks.clone(), self.reports.subscribe()),
+            Message::SinkStarted,
+        )
+    }
+
+    // save the snapshots as an image with the format and resolution from the config
+    fn export(&self, title: String, snapshots: Vec<Snapshot>) -> Command<Message> {
+        Command::perform(
+            export::save(
+                title,
+                snapshots,
+                self.config.export_format,
+                self.config.export_size,
+            ),
+            Message::ExportFinished,
+        )
+    }
+}
+
+// a button at the bottom of the sidebar which opens a page
+fn page_button(label: &str, route: Route) -> Element<'_, Message> {
+    Row::new()
+        .push(Space::new(Length::FillPortion(1), Length::Shrink))
+        .push(
+            Button::new(
+                Column::new()
+                    .push(Space::new(Length::Fill, Length::Fill))
+                    .push(
+                        Row::new()
+                            .push(Space::new(Length::Fill, Length::Shrink))
+                            .push(Text::new(label))
+                            .push(Space::new(Length::Fill, Length::Shrink)),
+                    )
+                    .push(Space::new(Length::Fill, Length::Fill)),
+            )
+            .on_press(Message::Navigate(route))
+            .style(theme::Button::Custom(Box::new(SettingsButton)))
+            .width(Length::FillPortion(3))
+            .height(Length::Fixed(50.0))
+            .padding(Padding::new(10.0)),
+        )
+        .push(Space::new(Length::FillPortion(1), Length::Shrink))
+        .into()
 }
 
 fn merge_maps(map1: &mut HashMap<String, bool>, map2: &HashMap<String, bool>) {
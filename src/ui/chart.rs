@@ -1,88 +1,1377 @@
 use std::collections::VecDeque;
+use std::fmt::Display;
+use std::ops::Range;
+use std::time::Instant;
 
+use chrono::{DateTime, Duration, Local, TimeZone};
+use iced::event::Status;
+use iced::mouse::{self, Cursor};
+use iced::widget::canvas::Event;
 use iced::Element;
 use iced::Length;
+use iced::{Point, Rectangle};
+use plotters::coord::types::RangedCoordf32;
+use plotters::coord::{ReverseCoordTranslate, Shift};
 use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 use plotters_backend::DrawingBackend;
 use plotters_iced::{Chart, ChartWidget};
 
 use crate::ui::Message;
 
+// the seconds per bucket and number of buckets kept for each rollup, finest first
+const ROLLUPS: [(i64, usize); 3] = [(1, 600), (10, 360), (60, 1440)];
+
+// buckets further apart than this many bucket widths are drawn with a gap between them
+const MAXIMUM_GAP_BUCKETS: f64 = 2.5;
+
+// graphs shorter than this are too small for a time axis
+const TIME_AXIS_MINIMUM_HEIGHT: u32 = 120;
+
+// two clicks closer together than this reset the zoom and pan
+const DOUBLE_CLICK_MILLISECONDS: u128 = 400;
+
+// the least amount of time a graph can be zoomed in to show
+const MINIMUM_VISIBLE_SECONDS: f64 = 10.0;
+
+// the number of bars in a histogram
+const HISTOGRAM_BINS: usize = 40;
+
+// how much one step of the mouse wheel zooms in or out
+const ZOOM_STEP: f64 = 0.8;
+
+// room left above the largest value so the line does not touch the top of the graph
+const SCALE_HEADROOM: f32 = 1.05;
+
+// the steps within each power of ten which the scale is rounded up to
+const SCALE_STEPS: [f32; 5] = [1.0, 2.0, 2.5, 5.0, 10.0];
+
+// the number of markers a graph keeps, the oldest are dropped first
+const MARKER_CAPACITY: usize = 100;
+
+// markers are drawn in red whatever the color of the line
+const MARKER_COLOR: (u8, u8, u8) = (235, 64, 52);
+
+// how much history a graph shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeWindow {
+    OneMinute,
+    TenMinutes,
+    OneHour,
+    Day,
+}
+
+impl TimeWindow {
+    pub const ALL: [Self; 4] = [Self::OneMinute, Self::TenMinutes, Self::OneHour, Self::Day];
+
+    // the length of the window
+    pub(crate) fn seconds(&self) -> i64 {
+        match self {
+            Self::OneMinute => 60,
+            Self::TenMinutes => 600,
+            Self::OneHour => 3600,
+            Self::Day => 86400,
+        }
+    }
+
+    // the finest rollup which covers the whole window
+    fn rollup(&self) -> usize {
+        ROLLUPS
+            .iter()
+            .position(|(resolution, capacity)| resolution * *capacity as i64 >= self.seconds())
+            .unwrap_or(ROLLUPS.len() - 1)
+    }
+}
+
+// the text for the pick list
+impl Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::OneMinute => "1 min",
+                Self::TenMinutes => "10 min",
+                Self::OneHour => "1 h",
+                Self::Day => "24 h",
+            }
+        )
+    }
+}
+
+// the data points which fall within a single bucket of a rollup
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start: DateTime<Local>,
+    minimum: f32,
+    maximum: f32,
+    total: f32,
+    count: u32,
+}
+
+impl Bucket {
+    fn average(&self) -> f32 {
+        self.total / self.count as f32
+    }
+}
+
+// data downsampled into fixed width buckets, spikes are kept in the bucket minimum and maximum
+#[derive(Debug, Clone)]
+struct Rollup {
+    // seconds per bucket
+    resolution: i64,
+    // maximum number of buckets
+    capacity: usize,
+    // oldest first
+    buckets: VecDeque<Bucket>,
+}
+
+impl Rollup {
+    fn new(resolution: i64, capacity: usize) -> Self {
+        Self {
+            resolution,
+            capacity,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, value: f32, timestamp: DateTime<Local>) {
+        let seconds = timestamp.timestamp();
+        let start = Local
+            .timestamp_opt(seconds - seconds.rem_euclid(self.resolution), 0)
+            .unwrap();
+
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.start == start => {
+                bucket.minimum = bucket.minimum.min(value);
+                bucket.maximum = bucket.maximum.max(value);
+                bucket.total += value;
+                bucket.count += 1;
+            }
+            _ => {
+                self.buckets.push_back(Bucket {
+                    start,
+                    minimum: value,
+                    maximum: value,
+                    total: value,
+                    count: 1,
+                });
+
+                if self.buckets.len() > self.capacity {
+                    self.buckets.pop_front();
+                }
+            }
+        }
+    }
+
+    // the buckets which fall within the window ending at the newest bucket
+    fn window(&self, window: TimeWindow) -> impl Iterator<Item = &Bucket> {
+        let oldest = self
+            .buckets
+            .back()
+            .map(|bucket| bucket.start - Duration::seconds(window.seconds()));
+
+        self.buckets
+            .iter()
+            .filter(move |bucket| oldest.is_some_and(|oldest| bucket.start > oldest))
+    }
+
+    // the buckets which start after oldest and no later than newest
+    fn range(
+        &self,
+        oldest: DateTime<Local>,
+        newest: DateTime<Local>,
+    ) -> impl Iterator<Item = &Bucket> {
+        self.buckets
+            .iter()
+            .filter(move |bucket| bucket.start > oldest && bucket.start <= newest)
+    }
+
+    // the number of seconds between the oldest and newest buckets
+    fn span(&self) -> f64 {
+        match (self.buckets.front(), self.buckets.back()) {
+            (Some(oldest), Some(newest)) => (newest.start - oldest.start).num_seconds() as f64,
+            _ => 0_f64,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct LineGraph {
-    // data
-    data_points: VecDeque<(i32, i32)>,
+    // the data at each resolution
+    rollups: Vec<Rollup>,
     // color of the line
     color: (u8, u8, u8),
-    // max value of the graph
-    pub(crate) maximum_value: i32,
-    // iterator for the x axis
-    iterator: i32,
+    // fixed minimum and maximum, otherwise the scale follows the data
+    bounds: Option<(f32, f32)>,
+    // flagged readings, oldest first
+    markers: VecDeque<(DateTime<Local>, f32)>,
 }
 
 impl LineGraph {
     pub(crate) fn new(color: (u8, u8, u8)) -> Self {
         Self {
-            data_points: VecDeque::with_capacity(102),
+            rollups: ROLLUPS
+                .iter()
+                .map(|(resolution, capacity)| Rollup::new(*resolution, *capacity))
+                .collect(),
             color,
-            maximum_value: 100,
-            iterator: 0,
+            bounds: None,
+            markers: VecDeque::new(),
         }
     }
 
+    // use a fixed scale instead of following the data
+    pub(crate) fn with_bounds(mut self, bounds: Option<(f32, f32)>) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
     // push data point to graph
-    pub(crate) fn push_data(&mut self, value: f32) {
-        // fill with zeros until we have 100 data points
-        while self.data_points.len() < 101 {
-            self.data_points.push_front((self.iterator, 0));
-            self.iterator += 1;
+    pub(crate) fn push_data(&mut self, value: f32, timestamp: DateTime<Local>) {
+        for rollup in &mut self.rollups {
+            rollup.push(value, timestamp);
+        }
+    }
+
+    // flag a reading, drawn as a marker on the graph
+    pub(crate) fn mark(&mut self, value: f32, timestamp: DateTime<Local>) {
+        self.markers.push_back((timestamp, value));
+
+        if self.markers.len() > MARKER_CAPACITY {
+            self.markers.pop_front();
+        }
+    }
+
+    pub(crate) fn clear_markers(&mut self) {
+        self.markers.clear();
+    }
+
+    // the markers which fall after oldest and no later than newest
+    fn markers(
+        &self,
+        oldest: DateTime<Local>,
+        newest: DateTime<Local>,
+    ) -> Vec<(DateTime<Local>, f32)> {
+        self.markers
+            .iter()
+            .filter(|(time, _)| *time > oldest && *time <= newest)
+            .copied()
+            .collect()
+    }
+
+    // the range of the y axis, rounded out from the data in the window so old spikes fall away
+    pub(crate) fn scale(&self, window: TimeWindow) -> Range<f32> {
+        self.scale_of(self.rollups[window.rollup()].window(window))
+    }
+
+    // the range of the y axis for the part of the window which is zoomed or panned to
+    fn visible_scale(
+        &self,
+        window: TimeWindow,
+        oldest: DateTime<Local>,
+        newest: DateTime<Local>,
+    ) -> Range<f32> {
+        self.scale_of(self.rollups[window.rollup()].range(oldest, newest))
+    }
+
+    fn scale_of<'a>(&self, buckets: impl Iterator<Item = &'a Bucket>) -> Range<f32> {
+        if let Some((minimum, maximum)) = self.bounds {
+            return minimum..maximum;
+        }
+
+        let (minimum, maximum) = buckets.fold((0_f32, 0_f32), |(minimum, maximum), bucket| {
+            (minimum.min(bucket.minimum), maximum.max(bucket.maximum))
+        });
+
+        let minimum = if minimum < 0_f32 {
+            -round_scale(-minimum * SCALE_HEADROOM)
+        } else {
+            0_f32
+        };
+
+        minimum..round_scale(maximum * SCALE_HEADROOM)
+    }
+
+    // the samples of both graphs which fall in the same bucket, oldest first
+    pub(crate) fn paired(
+        &self,
+        other: &LineGraph,
+        window: TimeWindow,
+    ) -> Vec<(DateTime<Local>, f32, f32)> {
+        let mut others = other.rollups[window.rollup()].window(window).peekable();
+        let mut pairs = Vec::new();
+
+        for bucket in self.rollups[window.rollup()].window(window) {
+            // skip buckets the other graph has which this one is missing
+            while others.next_if(|other| other.start < bucket.start).is_some() {}
+
+            if let Some(other) = others.next_if(|other| other.start == bucket.start) {
+                pairs.push((bucket.start, bucket.average(), other.average()));
+            }
+        }
+
+        pairs
+    }
+
+    // each bucket in the window as its average value and how many seconds it covers
+    pub(crate) fn samples(&self, window: TimeWindow) -> Vec<(f32, f32)> {
+        let rollup = &self.rollups[window.rollup()];
+
+        rollup
+            .window(window)
+            .map(|bucket| (bucket.average(), rollup.resolution as f32))
+            .collect()
+    }
+
+    // the buckets which start at or after since, from the finest rollup which reaches back that far,
+    // returns the seconds per bucket and each bucket's start, average, minimum and maximum
+    pub(crate) fn since(
+        &self,
+        since: DateTime<Local>,
+    ) -> (i64, Vec<(DateTime<Local>, f32, f32, f32)>) {
+        let newest = self.rollups[0]
+            .buckets
+            .back()
+            .map_or(since, |bucket| bucket.start);
+        let seconds = (newest - since).num_seconds();
+
+        let rollup = self
+            .rollups
+            .iter()
+            .find(|rollup| rollup.resolution * rollup.capacity as i64 >= seconds)
+            .unwrap_or(&self.rollups[self.rollups.len() - 1]);
+
+        let buckets = rollup
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.start >= since)
+            .map(|bucket| {
+                (
+                    bucket.start,
+                    bucket.average(),
+                    bucket.minimum,
+                    bucket.maximum,
+                )
+            })
+            .collect();
+
+        (rollup.resolution, buckets)
+    }
+
+    // the graph can be hovered, zoomed and panned
+    pub(crate) fn view(&self, window: TimeWindow) -> Element<Message> {
+        graph(vec![Series::new("", self, self.color)], window, true)
+    }
+
+    // the last minute of the graph, ignores the mouse so it can be placed inside buttons
+    pub(crate) fn thumbnail(&self) -> Element<Message> {
+        graph(
+            vec![Series::new("", self, self.color)],
+            TimeWindow::OneMinute,
+            false,
+        )
+    }
+}
+
+// one line of an overlay graph
+#[derive(Debug, Clone)]
+pub(crate) struct Series<'a> {
+    // shown in the legend
+    name: String,
+    graph: &'a LineGraph,
+    // overrides the color of the graph so overlaid lines can be told apart
+    color: (u8, u8, u8),
+}
+
+impl<'a> Series<'a> {
+    pub(crate) fn new(name: impl Into<String>, graph: &'a LineGraph, color: (u8, u8, u8)) -> Self {
+        Self {
+            name: name.into(),
+            graph,
+            color,
+        }
+    }
+}
+
+// several graphs drawn on one axis with a legend
+pub(crate) fn overlay(series: Vec<Series>, window: TimeWindow) -> Element<Message> {
+    graph(series, window, true)
+}
+
+// the last minute of several graphs, ignores the mouse so it can be placed inside buttons
+pub(crate) fn overlay_thumbnail(series: Vec<Series>) -> Element<Message> {
+    graph(series, TimeWindow::OneMinute, false)
+}
+
+// a copy of the whole window of several graphs, for saving as an image
+pub(crate) fn snapshot(
+    title: impl Into<String>,
+    series: &[Series],
+    window: TimeWindow,
+) -> Snapshot {
+    let view = GraphView {
+        series: series.to_vec(),
+        window,
+        interactive: false,
+    };
+
+    view.snapshot(title.into(), &GraphInteraction::default())
+}
+
+// the range of the y axis shared by the overlaid graphs
+pub(crate) fn overlay_scale(series: &[Series], window: TimeWindow) -> Range<f32> {
+    series
+        .iter()
+        .map(|series| series.graph.scale(window))
+        .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+        .unwrap_or(0_f32..1_f32)
+}
+
+fn graph(series: Vec<Series>, window: TimeWindow, interactive: bool) -> Element<Message> {
+    let chart = ChartWidget::new(GraphView {
+        series,
+        window,
+        interactive,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    chart.into()
+}
+
+// the top of a zoomed in scale, with a decimal only for small values
+fn format_scale(value: f32) -> String {
+    if value < 10_f32 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+// round up to the next step so the scale reads nicely
+fn round_scale(value: f32) -> f32 {
+    if value <= 0_f32 || !value.is_finite() {
+        return 1_f32;
+    }
+
+    let magnitude = 10_f32.powf(value.log10().floor());
+
+    SCALE_STEPS
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|step| *step >= value)
+        .unwrap_or(10_f32 * magnitude)
+}
+
+// the zoom, pan and hover state of a graph, kept by the chart widget between views
+pub(crate) struct GraphInteraction {
+    // cursor position relative to the graph
+    cursor: Option<Point>,
+    // the fraction of the time window which is visible
+    zoom: f64,
+    // how many seconds the view has been panned back from the newest data
+    offset: f64,
+    // the cursor position and offset when the drag started
+    drag: Option<(Point, f64)>,
+    // when the left button was last pressed, for detecting double clicks
+    last_click: Option<Instant>,
+}
+
+impl Default for GraphInteraction {
+    fn default() -> Self {
+        Self {
+            cursor: None,
+            zoom: 1_f64,
+            offset: 0_f64,
+            drag: None,
+            last_click: None,
+        }
+    }
+}
+
+// one or more line graphs drawn over a time window
+struct GraphView<'a> {
+    series: Vec<Series<'a>>,
+    window: TimeWindow,
+    interactive: bool,
+}
+
+impl GraphView<'_> {
+    fn rollups(&self) -> impl Iterator<Item = &Rollup> {
+        self.series
+            .iter()
+            .map(|series| &series.graph.rollups[self.window.rollup()])
+    }
+
+    // the number of seconds visible at the current zoom
+    fn visible_seconds(&self, state: &GraphInteraction) -> f64 {
+        (self.window.seconds() as f64 * state.zoom).max(MINIMUM_VISIBLE_SECONDS)
+    }
+
+    // keeps the view from being panned past either end of the history
+    fn clamp_offset(&self, state: &mut GraphInteraction) {
+        let span = self.rollups().map(Rollup::span).fold(0_f64, f64::max);
+        let maximum = (span - self.visible_seconds(state)).max(0_f64);
+        state.offset = state.offset.clamp(0_f64, maximum);
+    }
+
+    // the oldest and newest times visible
+    fn range(&self, state: &GraphInteraction) -> (DateTime<Local>, DateTime<Local>) {
+        let newest = self
+            .rollups()
+            .filter_map(|rollup| rollup.buckets.back())
+            .map(|bucket| bucket.start)
+            .max()
+            .unwrap_or_else(Local::now)
+            - Duration::milliseconds((state.offset * 1000.0) as i64);
+        let oldest = newest - Duration::milliseconds((self.visible_seconds(state) * 1000.0) as i64);
+
+        (oldest, newest)
+    }
+
+    // the range of the y axis shared by the graphs, following the visible data
+    fn scale(&self, state: &GraphInteraction) -> Range<f32> {
+        let (oldest, newest) = self.range(state);
+
+        self.series
+            .iter()
+            .map(|series| series.graph.visible_scale(self.window, oldest, newest))
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+            .unwrap_or(0_f32..1_f32)
+    }
+
+    // the visible part of the graphs, copied so it can be drawn after the view is gone
+    fn snapshot(&self, title: String, state: &GraphInteraction) -> Snapshot {
+        let (oldest, newest) = self.range(state);
+
+        Snapshot {
+            title,
+            lines: self
+                .series
+                .iter()
+                .zip(self.rollups())
+                .map(|(series, rollup)| SnapshotLine {
+                    name: series.name.clone(),
+                    color: series.color,
+                    segments: segments(rollup, oldest, newest),
+                    markers: series.graph.markers(oldest, newest),
+                })
+                .collect(),
+            resolution: ROLLUPS[self.window.rollup()].0,
+            oldest,
+            newest,
+            scale: self.scale(state),
+        }
+    }
+}
+
+// one graph of a snapshot
+#[derive(Debug, Clone)]
+struct SnapshotLine {
+    name: String,
+    color: (u8, u8, u8),
+    segments: Vec<Vec<Bucket>>,
+    markers: Vec<(DateTime<Local>, f32)>,
+}
+
+// graphs copied out of a view, drawn with a title, both axes and a legend when saved as an image
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    pub(crate) title: String,
+    lines: Vec<SnapshotLine>,
+    // seconds per bucket
+    resolution: i64,
+    oldest: DateTime<Local>,
+    newest: DateTime<Local>,
+    scale: Range<f32>,
+}
+
+impl Snapshot {
+    // the names of the graphs, used when the snapshot has no title
+    pub(crate) fn names(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .map(|line| line.name.as_str())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    // draw the snapshot in the area on a light background, text and axes are in the foreground color
+    pub(crate) fn draw<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        foreground: RGBColor,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let mut builder = ChartBuilder::on(area);
+
+        if !self.title.is_empty() {
+            builder.caption(
+                &self.title,
+                ("sans-serif", 20).into_font().color(&foreground),
+            );
+        }
+
+        let mut chart = builder
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(self.oldest..self.newest, self.scale.clone())?;
+
+        chart
+            .configure_mesh()
+            .axis_style(foreground)
+            .bold_line_style(foreground.mix(0.1))
+            .light_line_style(foreground.mix(0.04))
+            .x_labels(6)
+            .y_labels(6)
+            .label_style(("sans-serif", 14).into_font().color(&foreground))
+            .x_label_formatter(&|time: &DateTime<Local>| time.format("%H:%M:%S").to_string())
+            .y_label_formatter(&|value: &f32| compact(*value))
+            .draw()?;
+
+        let baseline = self.scale.start.max(0_f32);
+
+        for line in &self.lines {
+            let color = rgb(line.color);
+
+            for (index, segment) in line.segments.iter().enumerate() {
+                // the same band the view draws for downsampled data
+                if self.resolution > 1 {
+                    let band = segment
+                        .iter()
+                        .map(|bucket| (bucket.start, bucket.maximum))
+                        .chain(
+                            segment
+                                .iter()
+                                .rev()
+                                .map(|bucket| (bucket.start, bucket.minimum)),
+                        )
+                        .collect::<Vec<_>>();
+
+                    chart.draw_series(std::iter::once(Polygon::new(band, color.mix(0.2))))?;
+                }
+
+                let series = chart.draw_series(
+                    AreaSeries::new(
+                        segment
+                            .iter()
+                            .map(|bucket| (bucket.start, bucket.average())),
+                        baseline,
+                        color.mix(0.1),
+                    )
+                    .border_style(ShapeStyle::from(&color).stroke_width(2)),
+                )?;
+
+                // one legend entry per graph
+                if index == 0 {
+                    series.label(line.name.clone()).legend(move |(x, y)| {
+                        plotters::element::Rectangle::new(
+                            [(x, y - 5), (x + 10, y + 5)],
+                            color.filled(),
+                        )
+                    });
+                }
+            }
+
+            draw_markers(&mut chart, &line.markers, self.scale.clone(), 5)?;
+        }
+
+        if self.lines.len() > 1 {
+            chart
+                .configure_series_labels()
+                .position(SeriesLabelPosition::UpperLeft)
+                .label_font(("sans-serif", 14).into_font().color(&foreground))
+                .background_style(WHITE.mix(0.8))
+                .border_style(foreground.mix(0.5))
+                .draw()?;
+        }
+
+        Ok(())
+    }
+}
+
+// a line across the graph at each marker with a dot at the flagged reading
+fn draw_markers<DB: DrawingBackend>(
+    chart: &mut ChartContext<DB, Cartesian2d<RangedDateTime<DateTime<Local>>, RangedCoordf32>>,
+    markers: &[(DateTime<Local>, f32)],
+    scale: Range<f32>,
+    radius: i32,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let color = rgb(MARKER_COLOR);
+
+    for (time, value) in markers {
+        chart.draw_series(LineSeries::new(
+            [(*time, scale.start), (*time, scale.end)],
+            color.mix(0.5),
+        ))?;
+        chart.draw_series(std::iter::once(Circle::new(
+            (*time, *value),
+            radius,
+            color.filled(),
+        )))?;
+    }
+
+    Ok(())
+}
+
+// split the buckets into runs of consecutive buckets, so missed samples show up as gaps
+fn segments(rollup: &Rollup, oldest: DateTime<Local>, newest: DateTime<Local>) -> Vec<Vec<Bucket>> {
+    let maximum_gap =
+        Duration::milliseconds((rollup.resolution as f64 * MAXIMUM_GAP_BUCKETS * 1000.0) as i64);
+
+    let mut segments: Vec<Vec<Bucket>> = Vec::new();
+    let mut previous: Option<DateTime<Local>> = None;
+
+    for bucket in rollup.range(oldest, newest) {
+        match (previous, segments.last_mut()) {
+            (Some(time), Some(segment)) if bucket.start - time <= maximum_gap => {
+                segment.push(*bucket)
+            }
+            _ => segments.push(vec![*bucket]),
+        }
+
+        previous = Some(bucket.start);
+    }
+
+    segments
+}
+
+// large values shortened with a suffix so the axis labels stay narrow, 25000000 is 25M
+fn compact(value: f32) -> String {
+    let (value, suffix) = match value.abs() {
+        magnitude if magnitude >= 1e9 => (value / 1e9, "G"),
+        magnitude if magnitude >= 1e6 => (value / 1e6, "M"),
+        magnitude if magnitude >= 1e4 => (value / 1e3, "k"),
+        _ => (value, ""),
+    };
+
+    let number = format!("{:.2}", value);
+    format!(
+        "{}{}",
+        number.trim_end_matches('0').trim_end_matches('.'),
+        suffix
+    )
+}
+
+fn rgb(color: (u8, u8, u8)) -> RGBColor {
+    RGBColor(color.0, color.1, color.2)
+}
+
+// blue at zero through to red at one
+fn gradient(position: f64) -> HSLColor {
+    HSLColor(0.66 * (1_f64 - position.clamp(0_f64, 1_f64)), 0.9, 0.5)
+}
+
+impl Chart<Message> for GraphView<'_> {
+    type State = GraphInteraction;
+
+    // large graphs get a time axis along the bottom
+    fn draw_chart<DB: DrawingBackend>(&self, state: &Self::State, root: DrawingArea<DB, Shift>) {
+        let mut builder = ChartBuilder::on(&root);
+
+        if root.dim_in_pixel().1 >= TIME_AXIS_MINIMUM_HEIGHT {
+            builder.x_label_area_size(20);
+        }
+
+        self.build_chart(state, builder);
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+
+        // the axis takes the color of the first line
+        let axis_color = rgb(self.series.first().map_or((255, 255, 255), |s| s.color));
+        let resolution = ROLLUPS[self.window.rollup()].0;
+        let (oldest, newest) = self.range(state);
+        let scale = self.scale(state);
+        let baseline = scale.start.max(0_f32);
+
+        let mut chart = chart
+            .y_label_area_size(0)
+            .build_cartesian_2d(oldest..newest, scale.clone())
+            .expect("failed to build chart");
+
+        chart
+            .configure_mesh()
+            .disable_y_axis()
+            .axis_style(axis_color.mix(0.5))
+            .x_labels(5)
+            .x_label_style(("sans-serif", 12).into_font().color(&axis_color))
+            .x_label_formatter(&|time: &DateTime<Local>| time.format("%H:%M:%S").to_string())
+            .light_line_style(ShapeStyle {
+                color: TRANSPARENT,
+                filled: false,
+                stroke_width: 0,
+            })
+            .draw()
+            .unwrap();
+
+        let segments = self
+            .rollups()
+            .map(|rollup| segments(rollup, oldest, newest))
+            .collect::<Vec<_>>();
+
+        for (series, segments) in self.series.iter().zip(&segments) {
+            let color = rgb(series.color);
+
+            for segment in segments {
+                // downsampled data gets a band from the minimum to the maximum so spikes stay visible
+                if resolution > 1 {
+                    let band = segment
+                        .iter()
+                        .map(|bucket| (bucket.start, bucket.maximum))
+                        .chain(
+                            segment
+                                .iter()
+                                .rev()
+                                .map(|bucket| (bucket.start, bucket.minimum)),
+                        )
+                        .collect::<Vec<_>>();
+
+                    chart
+                        .draw_series(std::iter::once(Polygon::new(band, color.mix(0.2))))
+                        .expect("failed to draw chart data");
+                }
+
+                chart
+                    .draw_series(
+                        AreaSeries::new(
+                            segment
+                                .iter()
+                                .map(|bucket| (bucket.start, bucket.average())),
+                            baseline,
+                            color.mix(0.03), // the partially transparent area under the line
+                        )
+                        .border_style(ShapeStyle::from(&color).stroke_width(1)),
+                    )
+                    .expect("failed to draw chart data");
+            }
+
+            draw_markers(
+                &mut chart,
+                &series.graph.markers(oldest, newest),
+                scale.clone(),
+                3,
+            )
+            .expect("failed to draw markers");
+        }
+
+        // the maximum shown next to the graph is for the whole window, so a zoomed in graph
+        // labels its own in the top right corner
+        if scale != overlay_scale(&self.series, self.window) {
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    format_scale(scale.end),
+                    (newest, scale.end),
+                    ("sans-serif", 12)
+                        .into_font()
+                        .color(&axis_color)
+                        .pos(Pos::new(HPos::Right, VPos::Top)),
+                )))
+                .expect("failed to draw scale");
         }
 
-        let local_value = value as i32;
+        // overlaid graphs get a legend in the top left corner
+        if self.series.len() > 1 {
+            for (index, series) in self.series.iter().enumerate() {
+                let color = rgb(series.color);
+
+                chart
+                    .draw_series(std::iter::once(
+                        EmptyElement::at((oldest, scale.end))
+                            + Rectangle::new(
+                                [(6, 8 + 16 * index as i32), (16, 18 + 16 * index as i32)],
+                                color.filled(),
+                            )
+                            + Text::new(
+                                series.name.clone(),
+                                (22, 7 + 16 * index as i32),
+                                ("sans-serif", 12).into_font().color(&color),
+                            ),
+                    ))
+                    .expect("failed to draw legend");
+            }
+        }
+
+        // the crosshair follows the bucket nearest to the cursor
+        let Some(cursor) = state.cursor else {
+            return;
+        };
+
+        let Some((time, _)) = chart
+            .as_coord_spec()
+            .reverse_translate((cursor.x as i32, cursor.y as i32))
+        else {
+            return;
+        };
+
+        let nearest = segments
+            .iter()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .flatten()
+                    .min_by_key(|bucket| (bucket.start - time).num_milliseconds().abs())
+            })
+            .collect::<Vec<_>>();
+
+        let Some(time) = nearest.iter().flatten().map(|bucket| bucket.start).next() else {
+            return;
+        };
+
+        let crosshair = axis_color.mix(0.6);
+
+        chart
+            .draw_series(LineSeries::new(
+                [(time, scale.start), (time, scale.end)],
+                crosshair,
+            ))
+            .expect("failed to draw crosshair");
+
+        // one line of text for the time and one for each graph
+        let mut labels = vec![(time.format("%H:%M:%S").to_string(), axis_color)];
 
-        self.data_points.push_front((self.iterator, local_value));
-        self.iterator += 1;
+        for (series, bucket) in self.series.iter().zip(&nearest) {
+            let Some(bucket) = bucket else {
+                continue;
+            };
 
-        // limit data points
-        if self.data_points.len() > 101 {
-            self.data_points.pop_back();
+            let color = rgb(series.color);
+            let value = if resolution > 1 {
+                format!(
+                    "{:.2} ({:.2} - {:.2})",
+                    bucket.average(),
+                    bucket.minimum,
+                    bucket.maximum
+                )
+            } else {
+                format!("{:.2}", bucket.average())
+            };
+
+            labels.push(if series.name.is_empty() {
+                (value, color)
+            } else {
+                (format!("{}  {}", series.name, value), color)
+            });
+
+            chart
+                .draw_series(std::iter::once(Circle::new(
+                    (bucket.start, bucket.average()),
+                    3,
+                    color.filled(),
+                )))
+                .expect("failed to draw crosshair");
+
+            // a single graph also gets a horizontal line at its value
+            if self.series.len() == 1 {
+                chart
+                    .draw_series(LineSeries::new(
+                        [(oldest, bucket.average()), (newest, bucket.average())],
+                        crosshair,
+                    ))
+                    .expect("failed to draw crosshair");
+            }
         }
 
-        // store maximum value for the graph label
-        if local_value > self.maximum_value {
-            self.maximum_value = local_value
+        // the labels go on whichever side of the crosshair has more room
+        let (width, _) = chart.plotting_area().dim_in_pixel();
+        let label_width = labels
+            .iter()
+            .map(|(label, _)| label.len() as i32)
+            .max()
+            .unwrap_or_default()
+            * 7;
+        let x = if (cursor.x as u32) < width / 2 {
+            6
+        } else {
+            -6 - label_width
+        };
+
+        for (index, (label, color)) in labels.into_iter().enumerate() {
+            chart
+                .draw_series(std::iter::once(
+                    EmptyElement::at((time, scale.end))
+                        + Text::new(
+                            label,
+                            (x, 6 + 14 * index as i32),
+                            ("sans-serif", 12).into_font().color(&color),
+                        ),
+                ))
+                .expect("failed to draw crosshair");
         }
     }
 
-    pub(crate) fn view(&self) -> Element<Message> {
-        let chart = ChartWidget::new(self)
-            .width(Length::Fill)
-            .height(Length::Fill);
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (Status, Option<Message>) {
+        if !self.interactive {
+            return (Status::Ignored, None);
+        }
+
+        let Event::Mouse(event) = event else {
+            return (Status::Ignored, None);
+        };
+
+        state.cursor = cursor.position_in(bounds);
+
+        match event {
+            mouse::Event::CursorMoved { .. } => {
+                if let (Some((start, offset)), Some(position)) = (state.drag, cursor.position()) {
+                    // dragging right moves back in time
+                    let seconds_per_pixel = self.visible_seconds(state) / bounds.width as f64;
+                    state.offset = offset + (position.x - start.x) as f64 * seconds_per_pixel;
+                    self.clamp_offset(state);
+                }
+
+                (Status::Ignored, None)
+            }
+            mouse::Event::CursorLeft => {
+                state.cursor = None;
+                state.drag = None;
+                (Status::Ignored, None)
+            }
+            mouse::Event::ButtonPressed(mouse::Button::Left) if state.cursor.is_some() => {
+                let now = Instant::now();
+
+                if state.last_click.is_some_and(|last_click| {
+                    now.duration_since(last_click).as_millis() < DOUBLE_CLICK_MILLISECONDS
+                }) {
+                    *state = GraphInteraction {
+                        cursor: state.cursor,
+                        ..GraphInteraction::default()
+                    };
+                } else {
+                    state.last_click = Some(now);
+                    state.drag = cursor.position().map(|position| (position, state.offset));
+                }
 
-        chart.into()
+                (Status::Captured, None)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) if state.drag.is_some() => {
+                state.drag = None;
+                (Status::Captured, None)
+            }
+            // right clicking saves the visible part of the graph as an image
+            mouse::Event::ButtonPressed(mouse::Button::Right) if state.cursor.is_some() => (
+                Status::Captured,
+                Some(Message::ExportGraph(self.snapshot(String::new(), state))),
+            ),
+            mouse::Event::WheelScrolled { delta } if state.cursor.is_some() => {
+                let steps = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y as f64,
+                    mouse::ScrollDelta::Pixels { y, .. } => y as f64 / 50_f64,
+                };
+
+                // scrolling up zooms in, never further out than the whole window
+                let minimum = MINIMUM_VISIBLE_SECONDS / self.window.seconds() as f64;
+                state.zoom = (state.zoom * ZOOM_STEP.powf(steps)).clamp(minimum, 1_f64);
+                self.clamp_offset(state);
+
+                (Status::Captured, None)
+            }
+            _ => (Status::Ignored, None),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> mouse::Interaction {
+        if !self.interactive {
+            mouse::Interaction::default()
+        } else if state.drag.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
     }
 }
 
-impl Chart<Message> for LineGraph {
+// a scatter plot of paired samples from two sensors
+struct ScatterView {
+    points: Vec<(DateTime<Local>, f32, f32)>,
+    color: (u8, u8, u8),
+    // color the points from blue for the oldest to red for the newest
+    color_by_time: bool,
+}
+
+// a scatter plot of (time, x, y) samples
+pub(crate) fn scatter<'a>(
+    points: Vec<(DateTime<Local>, f32, f32)>,
+    color: (u8, u8, u8),
+    color_by_time: bool,
+) -> Element<'a, Message> {
+    let chart = ChartWidget::new(ScatterView {
+        points,
+        color,
+        color_by_time,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    chart.into()
+}
+
+// the range covering the values with a little room on either side
+fn padded_range(values: impl Iterator<Item = f32>) -> Range<f32> {
+    let (minimum, maximum) = values.fold((f32::MAX, f32::MIN), |(minimum, maximum), value| {
+        (minimum.min(value), maximum.max(value))
+    });
+
+    if minimum > maximum {
+        0_f32..1_f32
+    } else if minimum == maximum {
+        minimum - 1_f32..maximum + 1_f32
+    } else {
+        let padding = (maximum - minimum) * 0.05;
+        minimum - padding..maximum + padding
+    }
+}
+
+impl Chart<Message> for ScatterView {
     type State = ();
 
     fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut chart: ChartBuilder<DB>) {
         use plotters::prelude::*;
 
-        let color = RGBColor(self.color.0, self.color.1, self.color.2);
+        let color = rgb(self.color);
+        let x_range = padded_range(self.points.iter().map(|(_, x, _)| *x));
+        let y_range = padded_range(self.points.iter().map(|(_, _, y)| *y));
+
+        let mut chart = chart
+            .margin(10)
+            .x_label_area_size(20)
+            .y_label_area_size(50)
+            .build_cartesian_2d(x_range, y_range)
+            .expect("failed to build chart");
+
+        chart
+            .configure_mesh()
+            .axis_style(color.mix(0.5))
+            .label_style(("sans-serif", 12).into_font().color(&color))
+            .x_labels(8)
+            .y_labels(8)
+            .bold_line_style(color.mix(0.1))
+            .light_line_style(ShapeStyle {
+                color: TRANSPARENT,
+                filled: false,
+                stroke_width: 0,
+            })
+            .draw()
+            .unwrap();
+
+        let last = self.points.len().saturating_sub(1).max(1) as f64;
+
+        chart
+            .draw_series(self.points.iter().enumerate().map(|(index, (_, x, y))| {
+                let style = if self.color_by_time {
+                    gradient(index as f64 / last).filled()
+                } else {
+                    color.mix(0.6).filled()
+                };
+
+                Circle::new((*x, *y), 2, style)
+            }))
+            .expect("failed to draw chart data");
+    }
+}
+
+// rows of graphs drawn as colored cells, time along the x axis
+struct HeatmapView<'a> {
+    rows: Vec<&'a LineGraph>,
+    // color of the axes
+    color: (u8, u8, u8),
+    window: TimeWindow,
+}
+
+// one row of cells for each graph, the first graph along the bottom
+pub(crate) fn heatmap(
+    rows: Vec<&LineGraph>,
+    color: (u8, u8, u8),
+    window: TimeWindow,
+) -> Element<Message> {
+    let chart = ChartWidget::new(HeatmapView {
+        rows,
+        color,
+        window,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    chart.into()
+}
+
+// the values which the heatmap colors span, from the bounds of the graphs if they have them
+pub(crate) fn heatmap_range(rows: &[&LineGraph], window: TimeWindow) -> Range<f32> {
+    if let Some((minimum, maximum)) = rows.first().and_then(|graph| graph.bounds) {
+        return minimum..maximum;
+    }
+
+    let (minimum, maximum) = rows
+        .iter()
+        .flat_map(|graph| graph.rollups[window.rollup()].window(window))
+        .fold((f32::MAX, f32::MIN), |(minimum, maximum), bucket| {
+            (minimum.min(bucket.average()), maximum.max(bucket.average()))
+        });
 
-        let newest = self.data_points.front().unwrap_or(&(0, 0)).0;
-        let oldest = self.data_points.back().unwrap_or(&(0, 0)).0;
+    if minimum > maximum {
+        0_f32..1_f32
+    } else if minimum == maximum {
+        minimum..maximum + 1_f32
+    } else {
+        minimum..maximum
+    }
+}
 
+impl Chart<Message> for HeatmapView<'_> {
+    type State = ();
+
+    // large heatmaps get a time axis along the bottom
+    fn draw_chart<DB: DrawingBackend>(&self, state: &Self::State, root: DrawingArea<DB, Shift>) {
+        let mut builder = ChartBuilder::on(&root);
+
+        if root.dim_in_pixel().1 >= TIME_AXIS_MINIMUM_HEIGHT {
+            builder.x_label_area_size(20);
+        }
+
+        self.build_chart(state, builder);
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+
+        let color = rgb(self.color);
+        let (resolution, _) = ROLLUPS[self.window.rollup()];
+        let range = heatmap_range(&self.rows, self.window);
+
+        let newest = self
+            .rows
+            .iter()
+            .filter_map(|graph| graph.rollups[self.window.rollup()].buckets.back())
+            .map(|bucket| bucket.start + Duration::seconds(resolution))
+            .max()
+            .unwrap_or_else(Local::now);
+        let oldest = newest - Duration::seconds(self.window.seconds());
+
+        // rows are centered on their index so the labels line up with them
         let mut chart = chart
-            .x_label_area_size(0)
-            .y_label_area_size(0)
-            .build_cartesian_2d(oldest..newest, 0..self.maximum_value)
+            .y_label_area_size(30)
+            .build_cartesian_2d(oldest..newest, -0.5_f32..self.rows.len() as f32 - 0.5)
             .expect("failed to build chart");
 
         chart
             .configure_mesh()
-            .disable_axes()
+            .axis_style(color)
+            .x_labels(5)
+            .y_labels(self.rows.len().min(16))
+            .label_style(("sans-serif", 12).into_font().color(&color))
+            .x_label_formatter(&|time: &DateTime<Local>| time.format("%H:%M:%S").to_string())
+            .y_label_formatter(&|row: &f32| format!("{:.0}", row))
+            .disable_mesh()
+            .draw()
+            .unwrap();
+
+        for (index, graph) in self.rows.iter().enumerate() {
+            let row = index as f32;
+
+            chart
+                .draw_series(graph.rollups[self.window.rollup()].window(self.window).map(
+                    |bucket| {
+                        let position = (bucket.average() - range.start) / (range.end - range.start);
+
+                        Rectangle::new(
+                            [
+                                (bucket.start, row - 0.5),
+                                (bucket.start + Duration::seconds(resolution), row + 0.5),
+                            ],
+                            gradient(position as f64).filled(),
+                        )
+                    },
+                ))
+                .expect("failed to draw chart data");
+        }
+    }
+}
+
+// a histogram of weighted samples with labelled markers
+struct HistogramView {
+    // each value and its weight
+    samples: Vec<(f32, f32)>,
+    color: (u8, u8, u8),
+    // vertical lines drawn over the bars, such as percentiles
+    markers: Vec<(String, f32)>,
+}
+
+// the share of the total weight which falls in each bar
+pub(crate) fn histogram<'a>(
+    samples: Vec<(f32, f32)>,
+    color: (u8, u8, u8),
+    markers: Vec<(String, f32)>,
+) -> Element<'a, Message> {
+    let chart = ChartWidget::new(HistogramView {
+        samples,
+        color,
+        markers,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    chart.into()
+}
+
+impl Chart<Message> for HistogramView {
+    type State = ();
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+
+        let color = rgb(self.color);
+        let range = padded_range(self.samples.iter().map(|(value, _)| *value));
+        let width = (range.end - range.start) / HISTOGRAM_BINS as f32;
+        let total = self.samples.iter().map(|(_, weight)| weight).sum::<f32>();
+
+        let mut bins = [0_f32; HISTOGRAM_BINS];
+
+        for (value, weight) in &self.samples {
+            let bin = ((value - range.start) / width) as usize;
+            bins[bin.min(HISTOGRAM_BINS - 1)] += weight;
+        }
+
+        // the bars show the percentage of the time spent in each bin
+        let percentages = bins.map(|weight| {
+            if total > 0_f32 {
+                weight / total * 100_f32
+            } else {
+                0_f32
+            }
+        });
+        let maximum =
+            round_scale(percentages.iter().copied().fold(0_f32, f32::max) * SCALE_HEADROOM);
+
+        let mut chart = chart
+            .margin(10)
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .build_cartesian_2d(range.clone(), 0_f32..maximum)
+            .expect("failed to build chart");
+
+        chart
+            .configure_mesh()
+            .axis_style(color.mix(0.5))
+            .label_style(("sans-serif", 12).into_font().color(&color))
+            .x_labels(10)
+            .y_labels(5)
+            .y_label_formatter(&|percentage: &f32| format!("{:.0}%", percentage))
+            .bold_line_style(color.mix(0.1))
             .light_line_style(ShapeStyle {
                 color: TRANSPARENT,
                 filled: false,
@@ -92,14 +1381,31 @@ impl Chart<Message> for LineGraph {
             .unwrap();
 
         chart
-            .draw_series(
-                AreaSeries::new(
-                    self.data_points.iter().map(|x| (x.0, x.1)),
-                    0,
-                    color.mix(0.03), // the partially transparent area under the line
+            .draw_series(percentages.iter().enumerate().map(|(index, percentage)| {
+                let start = range.start + width * index as f32;
+
+                Rectangle::new(
+                    [(start, 0_f32), (start + width, *percentage)],
+                    color.mix(0.5).filled(),
                 )
-                .border_style(ShapeStyle::from(&color).stroke_width(1)),
-            )
+            }))
             .expect("failed to draw chart data");
+
+        for (label, value) in &self.markers {
+            chart
+                .draw_series(LineSeries::new([(*value, 0_f32), (*value, maximum)], color))
+                .expect("failed to draw marker");
+
+            chart
+                .draw_series(std::iter::once(
+                    EmptyElement::at((*value, maximum))
+                        + Text::new(
+                            label.clone(),
+                            (3, 2),
+                            ("sans-serif", 12).into_font().color(&color),
+                        ),
+                ))
+                .expect("failed to draw marker");
+        }
     }
 }
@@ -0,0 +1,222 @@
+use std::fmt::Display;
+use std::fs::create_dir_all;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use iced::widget::{button, text};
+use iced::{theme, Element};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::task::spawn_blocking;
+
+use crate::ui::chart::Snapshot;
+use crate::ui::style::button::SettingsButton;
+use crate::ui::{Message, Route};
+
+type Result<T> = std::result::Result<T, Error>;
+
+// images go here, resolved against the working directory like config.json
+const EXPORT_DIRECTORY: &str = "exports";
+
+// room at the top of the image for the title
+const TITLE_HEIGHT: u32 = 50;
+
+// error type
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io(io::Error),
+    Drawing(String),
+}
+
+// implement from for io error
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// implement from for the errors of every drawing backend
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for Error {
+    fn from(error: DrawingAreaErrorKind<E>) -> Self {
+        Self::Drawing(error.to_string())
+    }
+}
+
+// implement display for error
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Self::Io(error) => write!(f, "IO error: {}", error),
+            Self::Drawing(error) => write!(f, "Drawing error: {}", error),
+        }
+    }
+}
+
+// the file types images can be saved as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub const ALL: [Self; 2] = [Self::Png, Self::Svg];
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+// the text for the pick list
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Png => "PNG",
+                Self::Svg => "SVG",
+            }
+        )
+    }
+}
+
+// the resolutions images can be saved at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ImageSize {
+    Hd,
+    FullHd,
+    QuadHd,
+    UltraHd,
+}
+
+impl ImageSize {
+    pub const ALL: [Self; 4] = [Self::Hd, Self::FullHd, Self::QuadHd, Self::UltraHd];
+
+    // width and height in pixels
+    fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Self::Hd => (1280, 720),
+            Self::FullHd => (1920, 1080),
+            Self::QuadHd => (2560, 1440),
+            Self::UltraHd => (3840, 2160),
+        }
+    }
+}
+
+// the text for the pick list
+impl Display for ImageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = self.dimensions();
+        write!(f, "{} x {}", width, height)
+    }
+}
+
+// the button in the title bar of a page which saves its graphs
+pub(crate) fn save_button<'a>(route: Route) -> Element<'a, Message> {
+    button(text("Save Image").size(14))
+        .on_press(Message::ExportPage(route))
+        .style(theme::Button::Custom(Box::new(SettingsButton)))
+        .padding(5)
+        .into()
+}
+
+// asynchronously save the snapshots as one image, returns the path of the file
+pub(crate) async fn save(
+    title: String,
+    snapshots: Vec<Snapshot>,
+    format: ImageFormat,
+    size: ImageSize,
+) -> std::result::Result<PathBuf, String> {
+    spawn_blocking(move || {
+        write(&title, &snapshots, format, size).map_err(|error| error.to_string())
+    })
+    .await
+    .unwrap()
+}
+
+fn write(
+    title: &str,
+    snapshots: &[Snapshot],
+    format: ImageFormat,
+    size: ImageSize,
+) -> Result<PathBuf> {
+    create_dir_all(EXPORT_DIRECTORY)?;
+
+    let path = Path::new(EXPORT_DIRECTORY).join(format!(
+        "{}-{}.{}",
+        file_name(title),
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+
+    match format {
+        ImageFormat::Png => {
+            let root = BitMapBackend::new(&path, size.dimensions()).into_drawing_area();
+            draw(&root, title, snapshots)?;
+            root.present()?;
+        }
+        ImageFormat::Svg => {
+            let root = SVGBackend::new(&path, size.dimensions()).into_drawing_area();
+            draw(&root, title, snapshots)?;
+            root.present()?;
+        }
+    }
+
+    Ok(path)
+}
+
+// the title above a grid of the graphs, as close to square as the count allows
+fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    title: &str,
+    snapshots: &[Snapshot],
+) -> std::result::Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+
+    let (header, body) = root.split_vertically(TITLE_HEIGHT);
+
+    header.draw(&Text::new(
+        format!("{}  ({})", title, Local::now().format("%Y-%m-%d %H:%M:%S")),
+        (10, 12),
+        ("sans-serif", 26).into_font().color(&BLACK),
+    ))?;
+
+    let columns = (snapshots.len() as f64).sqrt().ceil().max(1_f64) as usize;
+    let rows = snapshots.len().div_ceil(columns).max(1);
+
+    for (snapshot, area) in snapshots.iter().zip(body.split_evenly((rows, columns))) {
+        snapshot.draw(&area, BLACK)?;
+    }
+
+    Ok(())
+}
+
+// the title with anything which is not allowed in a file name replaced
+fn file_name(title: &str) -> String {
+    let name = title
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() {
+                character.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if name.is_empty() {
+        String::from("graph")
+    } else {
+        name
+    }
+}
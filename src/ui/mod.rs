@@ -1,5 +1,5 @@
-use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use iced::window::icon::from_rgba;
@@ -11,11 +11,14 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use crate::config::Config;
-use crate::system::{HardwareMonitor, SystemStats};
+use crate::server::Server;
+use crate::system::{HardwareMonitor, Report};
 use crate::ui::app::App;
+use crate::ui::chart::TimeWindow;
 
 mod app;
 pub(crate) mod chart;
+pub(crate) mod export;
 pub(crate) mod style;
 
 const ICON: &[u8] = include_bytes!("../../icon.ico");
@@ -53,24 +56,108 @@ impl Display for Theme {
 pub(crate) enum Message {
     // emitted every second to update the stats
     Update,
-    // message contains the updated stats object
-    UpdateCompleted(Box<(SystemStats, HashMap<String, bool>)>),
+    // message contains the report read from the hardware monitor
+    UpdateCompleted(Arc<Report>),
     // message contains the hardware monitor reference
     MonitorCreated(Arc<Mutex<HardwareMonitor>>),
     // message for navigating between pages
     Navigate(Route),
     // cpu pick list changed
     CpuPickChanged(crate::system::cpu::GraphState),
+    // cpu heatmap metric pick list changed
+    CpuHeatmapChanged(crate::system::cpu::GraphState),
     // gpu pick list changed
     GpuPickChanged(crate::system::gpu::GraphState),
     // storage pick list changed
     StoragePickChanged(crate::system::storage::GraphState),
+    // compare pick list changed
+    ComparePickChanged(crate::system::compare::GraphState),
+    // a sensor was picked for the x axis of the correlation page
+    CorrelationXChanged(crate::system::history::SensorChoice),
+    // a sensor was picked for the y axis of the correlation page
+    CorrelationYChanged(crate::system::history::SensorChoice),
+    // coloring the correlation points by time was toggled
+    CorrelationColorToggled(bool),
+    // a sensor was picked on the distribution page
+    DistributionSensorChanged(crate::system::history::SensorChoice),
+    // the time above threshold input changed
+    DistributionThresholdChanged(String),
+    // anomaly detection was turned on or off
+    AnomalyDetectionToggled(bool),
+    // an anomaly was picked, contains the identifier of its sensor
+    AnomalySelected(String),
+    // clear the list of anomalies
+    AnomaliesCleared,
+    // time window pick list changed on a page
+    TimeWindowChanged(Route, TimeWindow),
+    // statistics window pick list changed, shared by every page
+    StatisticsWindowChanged(crate::system::statistics::StatisticsWindow),
+    // reset the statistics of the page's hardware
+    StatisticsReset(Route),
+    // the hardware monitor finished resetting its minimum and maximum values
+    StatisticsResetCompleted(()),
     // app theme changed
     ThemeChanged(Theme),
     // temperature unit changed
     TemperatureUnitChanged,
     // visibility changed
     VisibilityChanged((String, bool)),
+    // start or stop recording to a parquet file
+    RecordingToggled,
+    // a report was written to the recording with this number
+    RecordingWritten(usize, Result<(), String>),
+    // the recording was finished, contains the path of the file
+    RecordingFinished(Result<PathBuf, String>),
+    // save the visible part of a graph as an image, sent when a graph is right clicked
+    ExportGraph(chart::Snapshot),
+    // save every graph on a page as one image
+    ExportPage(Route),
+    // the image was saved, contains the path of the file
+    ExportFinished(Result<PathBuf, String>),
+    // an alert action finished, contains the error if it failed
+    AlertActionFinished(Result<(), String>),
+    // the local server started listening or failed to
+    ServerStarted(Result<Arc<Server>, String>),
+    // the prometheus endpoint was turned on or off
+    ServerMetricsToggled(bool),
+    // the json api was turned on or off
+    ServerApiToggled(bool),
+    // the websocket stream was turned on or off
+    ServerStreamToggled(bool),
+    // the web dashboard was turned on or off
+    ServerDashboardToggled(bool),
+    // the server address input changed
+    ServerAddressChanged(String),
+    // the server address input was submitted, restarting the server on it
+    ServerAddressSubmitted,
+    // the mqtt publisher started connecting
+    MqttStarted(Arc<crate::mqtt::Publisher>),
+    // publishing to the mqtt broker was turned on or off
+    MqttToggled(bool),
+    // home assistant discovery was turned on or off
+    MqttDiscoveryToggled(bool),
+    // an output started writing
+    SinkStarted(Arc<crate::sink::Sink>),
+    // an output was turned on or off
+    SinkToggled(crate::sink::Kind, bool),
+    // the connection to another instance changed or sent readings
+    Remote(crate::remote::Event),
+    // showing another instance was turned on or off
+    RemoteToggled(bool),
+    // the remote address input changed
+    RemoteAddressChanged(String),
+    // the remote address input was submitted, connecting to it
+    RemoteAddressSubmitted,
+    // a host on the fleet page was polled
+    Fleet(crate::fleet::Event),
+    // a fleet tile was pressed, showing that host's pages
+    FleetHostSelected(usize),
+    // image file type pick list changed
+    ExportFormatChanged(export::ImageFormat),
+    // image resolution pick list changed
+    ExportSizeChanged(export::ImageSize),
+    // the window was asked to close
+    CloseRequested,
 }
 
 // GUI routes
@@ -81,6 +168,11 @@ pub(crate) enum Route {
     Ram,
     Storage(usize),
     Network(usize),
+    Compare,
+    Correlation,
+    Distribution,
+    Anomalies,
+    Fleet,
     Settings,
 }
 
@@ -114,7 +206,7 @@ fn settings() -> Settings<Config> {
         flags: Config::load().expect("failed to load config"), // load config
         default_font: Font::DEFAULT,
         default_text_size: 20.0,
-        exit_on_close_request: true,
+        exit_on_close_request: false, // the recording needs to be finished before exiting
         antialiasing: true,
     }
 }
@@ -2,13 +2,38 @@ use iced::widget::button;
 use iced::widget::button::Appearance;
 use iced::{color, BorderRadius, Color, Theme};
 
-pub(crate) struct ComponentSelect;
+use crate::alert::Severity;
+use crate::ui::style::severity_color;
+
+// the sidebar buttons, outlined in the color of the most severe active alert
+pub(crate) struct ComponentSelect {
+    pub(crate) alert: Option<Severity>,
+}
+
+impl ComponentSelect {
+    // outline the button when the hardware has an active alert, tinting it unless hovered
+    fn highlight(&self, appearance: Appearance) -> Appearance {
+        let color = match self.alert {
+            Some(severity) => severity_color(severity),
+            None => return appearance,
+        };
+
+        Appearance {
+            background: appearance
+                .background
+                .or(Some(Color { a: 0.15, ..color }.into())),
+            border_width: 2_f32,
+            border_color: color,
+            ..appearance
+        }
+    }
+}
 
 impl button::StyleSheet for ComponentSelect {
     type Style = Theme;
 
     fn active(&self, style: &Self::Style) -> Appearance {
-        match style {
+        let appearance = match style {
             Theme::Light => Appearance {
                 background: None,
                 border_radius: BorderRadius::from(8_f32),
@@ -23,11 +48,13 @@ impl button::StyleSheet for ComponentSelect {
                 text_color: Color::WHITE,
                 ..Appearance::default()
             },
-        }
+        };
+
+        self.highlight(appearance)
     }
 
     fn hovered(&self, style: &Self::Style) -> Appearance {
-        match style {
+        let appearance = match style {
             Theme::Light => Appearance {
                 background: Some(color!(214, 214, 214).into()),
                 border_radius: BorderRadius::from(8_f32),
@@ -42,11 +69,13 @@ impl button::StyleSheet for ComponentSelect {
                 text_color: Color::WHITE,
                 ..Appearance::default()
             },
-        }
+        };
+
+        self.highlight(appearance)
     }
 
     fn pressed(&self, style: &Self::Style) -> Appearance {
-        match style {
+        let appearance = match style {
             Theme::Light => Appearance {
                 background: Some(color!(234, 234, 234).into()),
                 border_radius: BorderRadius::from(8_f32),
@@ -61,7 +90,9 @@ impl button::StyleSheet for ComponentSelect {
                 text_color: Color::WHITE,
                 ..Appearance::default()
             },
-        }
+        };
+
+        self.highlight(appearance)
     }
 }
 
@@ -2,6 +2,9 @@ use iced::widget::container;
 use iced::widget::container::Appearance;
 use iced::{color, BorderRadius, Color, Theme};
 
+use crate::alert::Severity;
+use crate::ui::style::severity_color;
+
 pub(crate) struct MainBox;
 
 impl container::StyleSheet for MainBox {
@@ -102,3 +105,30 @@ impl container::StyleSheet for GraphBox {
         }
     }
 }
+
+// a filled block of a timeline, colored by severity
+pub(crate) struct Segment {
+    color: Color,
+}
+
+impl Segment {
+    pub(crate) fn new(severity: Severity) -> Self {
+        Self {
+            color: severity_color(severity),
+        }
+    }
+}
+
+impl container::StyleSheet for Segment {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> Appearance {
+        Appearance {
+            text_color: None,
+            background: Some(self.color.into()),
+            border_radius: BorderRadius::from(2_f32),
+            border_width: 0_f32,
+            border_color: Default::default(),
+        }
+    }
+}
@@ -3,3 +3,16 @@ pub(crate) mod container;
 pub(crate) mod pick_list;
 pub(crate) mod scrollable;
 pub(crate) mod toggler;
+
+use iced::{color, Color};
+
+use crate::alert::Severity;
+
+// the color alerts and throttling episodes are highlighted with
+pub(crate) fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => color!(100, 160, 255),
+        Severity::Warning => color!(255, 190, 0),
+        Severity::Critical => color!(235, 64, 52),
+    }
+}